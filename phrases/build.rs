@@ -0,0 +1,233 @@
+// Generates `PhraseKey` from the reference catalog and compiles every
+// catalog in `catalogs/` into static tables.
+
+extern crate alloc;
+extern crate core;
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[path = "src/syntax.rs"]
+mod syntax;
+
+const CATALOG_DIR: &str = "catalogs";
+const REFERENCE: &str = "en";
+const CATEGORIES: &[&str] = &["zero", "one", "two", "few", "many", "other"];
+const REGISTERS: &[&str] = &["casual", "polite", "formal"];
+
+struct Key {
+    name: String,
+    variant: String,
+    plural: bool,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", CATALOG_DIR);
+    println!("cargo:rerun-if-changed=src/syntax.rs");
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(CATALOG_DIR)
+        .expect("could not read catalogs/")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("catalog"))
+        .collect();
+    paths.sort();
+
+    let catalogs: Vec<(String, Vec<syntax::Entry>)> = paths
+        .iter()
+        .map(|path| {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let tag = path.file_stem().unwrap().to_str().unwrap().to_string();
+            let source = fs::read_to_string(path).unwrap();
+            let entries =
+                syntax::parse(&source).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+            (tag, entries)
+        })
+        .collect();
+
+    let reference = catalogs
+        .iter()
+        .find(|&(tag, _)| tag == REFERENCE)
+        .unwrap_or_else(|| {
+            panic!(
+                "missing reference catalog {}/{}.catalog",
+                CATALOG_DIR, REFERENCE
+            )
+        });
+    let keys = keys(&reference.1);
+
+    for (tag, entries) in &catalogs {
+        for entry in entries {
+            let name = match entry.key.rfind('@') {
+                Some(at) if REGISTERS.contains(&&entry.key[at + 1..]) => &entry.key[..at],
+                Some(_) => panic!(
+                    "{}/{}.catalog: line {}: `{}` names an unknown register",
+                    CATALOG_DIR, tag, entry.line, entry.key
+                ),
+                None => entry.key.as_str(),
+            };
+            if key_of(&keys, name).is_none() {
+                panic!(
+                    "{}/{}.catalog: line {}: `{}` is not a key of the reference catalog",
+                    CATALOG_DIR, tag, entry.line, entry.key
+                );
+            }
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    write(
+        &Path::new(&out_dir).join("phrase_key.rs"),
+        &phrase_key(&keys),
+    );
+    write(&Path::new(&out_dir).join("builtin.rs"), &builtin(&catalogs));
+}
+
+// A key is plural when the catalog gives it per-category forms, e.g.
+// `farewells.farewells.one` and `farewells.farewells.other`. Register forms
+// (`greetings.hello@casual`) do not define keys of their own.
+fn keys(entries: &[syntax::Entry]) -> Vec<Key> {
+    let entries: Vec<&syntax::Entry> = entries
+        .iter()
+        .filter(|entry| !entry.key.contains('@'))
+        .collect();
+    let names: Vec<&str> = entries.iter().map(|entry| entry.key.as_str()).collect();
+    let mut keys: Vec<Key> = Vec::new();
+
+    for entry in entries {
+        let (name, plural) = match plural_base(&entry.key) {
+            Some(base) if names.contains(&format!("{}.other", base).as_str()) => (base, true),
+            _ => (entry.key.as_str(), false),
+        };
+        if keys.iter().any(|key| key.name == name) {
+            continue;
+        }
+
+        let variant = variant(name);
+        if let Some(clash) = keys.iter().find(|key| key.variant == variant) {
+            panic!(
+                "`{}` and `{}` would both generate `PhraseKey::{}`",
+                clash.name, name, variant
+            );
+        }
+        keys.push(Key {
+            name: name.to_string(),
+            variant,
+            plural,
+        });
+    }
+    keys
+}
+
+fn plural_base(name: &str) -> Option<&str> {
+    let at = name.rfind('.')?;
+    if CATEGORIES.contains(&&name[at + 1..]) {
+        Some(&name[..at])
+    } else {
+        None
+    }
+}
+
+fn key_of<'a>(keys: &'a [Key], name: &str) -> Option<&'a Key> {
+    keys.iter()
+        .find(|key| !key.plural && key.name == name)
+        .or_else(|| {
+            let base = plural_base(name)?;
+            keys.iter().find(|key| key.plural && key.name == base)
+        })
+}
+
+// `greetings.hello_to` becomes `HelloTo`.
+fn variant(name: &str) -> String {
+    let last = name.rsplit('.').next().unwrap();
+    last.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            Some(first).into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+fn phrase_key(keys: &[Key]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]"
+    )
+    .unwrap();
+    writeln!(out, "pub enum PhraseKey {{").unwrap();
+    for key in keys {
+        writeln!(out, "    {},", key.variant).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl PhraseKey {{").unwrap();
+    writeln!(out, "    pub fn all() -> &'static [PhraseKey] {{").unwrap();
+    writeln!(out, "        &[").unwrap();
+    for key in keys {
+        writeln!(out, "            PhraseKey::{},", key.variant).unwrap();
+    }
+    writeln!(out, "        ]\n    }}\n").unwrap();
+
+    writeln!(out, "    pub fn name(&self) -> &'static str {{").unwrap();
+    writeln!(out, "        match *self {{").unwrap();
+    for key in keys {
+        writeln!(
+            out,
+            "            PhraseKey::{} => {:?},",
+            key.variant, key.name
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}\n    }}\n").unwrap();
+
+    let plural: Vec<String> = keys
+        .iter()
+        .filter(|key| key.plural)
+        .map(|key| format!("PhraseKey::{}", key.variant))
+        .collect();
+    writeln!(out, "    pub fn is_plural(&self) -> bool {{").unwrap();
+    if plural.is_empty() {
+        writeln!(out, "        false").unwrap();
+    } else {
+        writeln!(out, "        matches!(*self, {})", plural.join(" | ")).unwrap();
+    }
+    writeln!(out, "    }}\n}}").unwrap();
+    out
+}
+
+fn builtin(catalogs: &[(String, Vec<syntax::Entry>)]) -> String {
+    let mut out = String::new();
+    let mut statics = Vec::new();
+
+    for (tag, entries) in catalogs {
+        let ident = tag.to_uppercase().replace('-', "_");
+        let mut entries: Vec<&syntax::Entry> = entries.iter().collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+        writeln!(out, "pub static {}: Builtin = Builtin {{", ident).unwrap();
+        writeln!(out, "    tag: {:?},", tag).unwrap();
+        writeln!(out, "    entries: &[").unwrap();
+        for entry in entries {
+            writeln!(out, "        ({:?}, {:?}),", entry.key, entry.value).unwrap();
+        }
+        writeln!(out, "    ],\n}};\n").unwrap();
+        statics.push(format!("&{}", ident));
+    }
+
+    writeln!(
+        out,
+        "pub static ALL: &[&Builtin] = &[{}];",
+        statics.join(", ")
+    )
+    .unwrap();
+    out
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::write(path, contents).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+}
@@ -0,0 +1,432 @@
+// `phrase!`, which looks a phrase up by its key in the reference catalog
+// and formats it for the ambient locale of `phrases::context`:
+//
+//     phrase!("greetings.hello")
+//     phrase!("greetings.hello_to", name = user.name)
+//     phrase!("farewells.farewells", count = 3)
+//     phrase!("greetings.welcome", name = user.name; gender = user.gender)
+//
+// The key must be one of `catalogs/en.catalog`, and the arguments before
+// the `;` exactly the placeholders it takes there, which
+// `PhraseKey::placeholders` lists; both are checked when the call site
+// compiles.
+// A count-dependent phrase takes its `count` as a `u64`. Arguments after
+// the `;` are attributes that translations may select on, and are not
+// checked. The call evaluates to a `String`.
+//
+// The expansion names `::phrases`, which the calling crate must link.
+
+extern crate phrases;
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use phrases::PhraseKey;
+
+#[proc_macro]
+pub fn phrase(input: TokenStream) -> TokenStream {
+    match parse(input).and_then(|call| expand(&call)) {
+        Ok(tokens) => tokens,
+        Err((span, message)) => compile_error(span, &message),
+    }
+}
+
+type Parse<T> = Result<T, (Span, String)>;
+
+struct Argument {
+    name: Ident,
+    value: TokenStream,
+}
+
+struct Call {
+    key: String,
+    key_span: Span,
+    args: Vec<Argument>,
+    attributes: Vec<Argument>,
+}
+
+fn parse(input: TokenStream) -> Parse<Call> {
+    let mut tokens = input.into_iter().peekable();
+    let (key, key_span) = match tokens.next() {
+        Some(TokenTree::Literal(literal)) => {
+            let text = literal.to_string();
+            if text.len() < 2
+                || !text.starts_with('"')
+                || !text.ends_with('"')
+                || text.contains('\\')
+            {
+                return Err((
+                    literal.span(),
+                    "expected a phrase key such as \"greetings.hello\"".to_string(),
+                ));
+            }
+            (text[1..text.len() - 1].to_string(), literal.span())
+        }
+        Some(token) => {
+            return Err((
+                token.span(),
+                "expected a phrase key such as \"greetings.hello\"".to_string(),
+            ))
+        }
+        None => {
+            return Err((
+                Span::call_site(),
+                "`phrase!` needs a phrase key".to_string(),
+            ))
+        }
+    };
+
+    let mut call = Call {
+        key,
+        key_span,
+        args: Vec::new(),
+        attributes: Vec::new(),
+    };
+    let mut in_attributes = false;
+    while let Some(separator) = tokens.next() {
+        match separator {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => {}
+            TokenTree::Punct(ref punct) if punct.as_char() == ';' && !in_attributes => {
+                in_attributes = true;
+            }
+            token => return Err((token.span(), "expected `,` or `;`".to_string())),
+        }
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(name)) => name,
+            Some(token) => return Err((token.span(), "expected `name = value`".to_string())),
+            // A trailing separator.
+            None => break,
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '=' => {}
+            _ => return Err((name.span(), format!("expected `{} = value`", name))),
+        }
+
+        let mut value = TokenStream::new();
+        while let Some(token) = tokens.peek() {
+            if let TokenTree::Punct(ref punct) = *token {
+                if punct.as_char() == ',' || punct.as_char() == ';' {
+                    break;
+                }
+            }
+            value.extend(tokens.next());
+        }
+        if value.is_empty() {
+            return Err((name.span(), format!("`{}` needs a value", name)));
+        }
+
+        let argument = Argument { name, value };
+        let section = if in_attributes {
+            &mut call.attributes
+        } else {
+            &mut call.args
+        };
+        let name = argument.name.to_string();
+        if section.iter().any(|other| other.name.to_string() == name) {
+            return Err((argument.name.span(), format!("`{}` is given twice", name)));
+        }
+        section.push(argument);
+    }
+    Ok(call)
+}
+
+fn expand(call: &Call) -> Parse<TokenStream> {
+    let key = PhraseKey::from_name(&call.key).ok_or_else(|| {
+        (
+            call.key_span,
+            format!("`{}` is not a key of the reference catalog", call.key),
+        )
+    })?;
+
+    let expected = key.placeholders();
+    for argument in &call.args {
+        let name = argument.name.to_string();
+        if !expected.contains(&name) {
+            return Err((
+                argument.name.span(),
+                format!(
+                    "`{}` has no placeholder `{}`; it takes {}",
+                    call.key,
+                    name,
+                    braced(&expected)
+                ),
+            ));
+        }
+    }
+    let missing: Vec<String> = expected
+        .iter()
+        .filter(|name| {
+            !call
+                .args
+                .iter()
+                .any(|argument| argument.name.to_string() == **name)
+        })
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err((
+            call.key_span,
+            format!("`{}` needs a value for {}", call.key, braced(&missing)),
+        ));
+    }
+
+    let mut body = tokens("let mut __phrase_args = ::phrases::Args::new();");
+    let mut count = None;
+    for argument in &call.args {
+        if key.is_plural() && argument.name.to_string() == "count" {
+            count = Some(argument.value.clone());
+            continue;
+        }
+        body.extend(method(
+            "set",
+            &argument.name,
+            tokens("&")
+                .into_iter()
+                .chain(Some(parenthesized(argument.value.clone())))
+                .collect(),
+        ));
+    }
+    for attribute in &call.attributes {
+        let mut value = tokens("&::std::string::ToString::to_string");
+        value.extend(Some(parenthesized(
+            tokens("&")
+                .into_iter()
+                .chain(Some(parenthesized(attribute.value.clone())))
+                .collect(),
+        )));
+        body.extend(method("set_attribute", &attribute.name, value));
+    }
+
+    let key_path = format!("::phrases::PhraseKey::{:?}", key);
+    match count {
+        Some(count) => {
+            body.extend(tokens("::phrases::context::format_count"));
+            let mut args = tokens(&format!("{},", key_path));
+            args.extend(Some(parenthesized(count)));
+            args.extend(tokens(", &__phrase_args"));
+            body.extend(Some(parenthesized(args)));
+        }
+        None => body.extend(tokens(&format!(
+            "::phrases::context::format({}, &__phrase_args)",
+            key_path
+        ))),
+    }
+    Ok(Some(TokenTree::Group(Group::new(Delimiter::Brace, body)))
+        .into_iter()
+        .collect())
+}
+
+fn braced(names: &[String]) -> String {
+    if names.is_empty() {
+        return "no placeholders".to_string();
+    }
+    let names: Vec<String> = names.iter().map(|name| format!("{{{}}}", name)).collect();
+    names.join(", ")
+}
+
+fn tokens(source: &str) -> TokenStream {
+    source.parse().unwrap()
+}
+
+// `(inner)`, spanned like the first of `inner` so that type errors in a
+// caller's value point at it.
+fn parenthesized(inner: TokenStream) -> TokenTree {
+    let span = inner
+        .clone()
+        .into_iter()
+        .next()
+        .map_or_else(Span::call_site, |token| token.span());
+    let mut group = Group::new(Delimiter::Parenthesis, inner);
+    group.set_span(span);
+    TokenTree::Group(group)
+}
+
+// `__phrase_args.method("name", value);`
+fn method(method: &str, name: &Ident, value: TokenStream) -> TokenStream {
+    let mut args: TokenStream = Some(TokenTree::Literal(Literal::string(&name.to_string())))
+        .into_iter()
+        .collect();
+    args.extend(tokens(","));
+    args.extend(value);
+
+    let mut out = tokens(&format!("__phrase_args.{}", method));
+    out.extend(Some(parenthesized(args)));
+    out.extend(tokens(";"));
+    out
+}
+
+// Every error `phrase!` reports, one call per doctest; the first shows
+// that the same calls compile when they are right.
+//
+/// ```
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// let _: String = phrase!("greetings.welcome", name = "Ana"; gender = "female");
+/// let _: String = phrase!("farewells.farewells", count = 3);
+/// # }
+/// ```
+///
+/// A key that is not a string literal.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!(greetings.hello);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!(b"greetings.hello");
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings\x2Ehello");
+/// # }
+/// ```
+///
+/// No key at all.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!();
+/// # }
+/// ```
+///
+/// Arguments not separated from the key.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to" name = "Sam");
+/// # }
+/// ```
+///
+/// A third section.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.welcome", name = "Ana"; gender = "female"; age = 30);
+/// # }
+/// ```
+///
+/// An argument without a name.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to", "Sam");
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to", name "Sam");
+/// # }
+/// ```
+///
+/// An argument without a value.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to", name = );
+/// # }
+/// ```
+///
+/// An argument given twice.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to", name = "Sam", name = "Ana");
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.welcome", name = "Ana"; gender = "female", gender = "male");
+/// # }
+/// ```
+///
+/// A key missing from the reference catalog.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.howdy");
+/// # }
+/// ```
+///
+/// An argument the phrase has no placeholder for.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello", name = "Sam");
+/// # }
+/// ```
+///
+/// A placeholder without an argument.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("greetings.hello_to");
+/// # }
+/// ```
+///
+/// A count that is not a number.
+///
+/// ```compile_fail,E0308
+/// # #[macro_use] extern crate phrases_macros;
+/// # extern crate phrases;
+/// # fn main() {
+/// phrase!("farewells.farewells", count = "three");
+/// # }
+/// ```
+#[allow(dead_code)]
+enum CompileErrors {}
+
+// `compile_error!("message")`, reported at `span`.
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut group = Group::new(
+        Delimiter::Parenthesis,
+        Some(TokenTree::Literal(literal)).into_iter().collect(),
+    );
+    group.set_span(span);
+    vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ]
+    .into_iter()
+    .collect()
+}
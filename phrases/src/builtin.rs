@@ -0,0 +1,56 @@
+use alloc::string::{String, ToString};
+
+use catalog::plural_name;
+use locale::Locale;
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::PluralCategory;
+use register::{register_name, Register};
+
+// A catalog from `catalogs/` compiled into the crate by the build script.
+// `entries` are `(key, text)` pairs sorted by key.
+pub struct Builtin {
+    tag: &'static str,
+    entries: &'static [(&'static str, &'static str)],
+}
+
+impl Builtin {
+    pub fn get(&self, name: &str) -> Option<&'static str> {
+        self.entries
+            .binary_search_by(|&(key, _)| key.cmp(name))
+            .ok()
+            .map(|at| self.entries[at].1)
+    }
+}
+
+impl Phrasebook for Builtin {
+    fn locale(&self) -> Locale {
+        self.tag.parse().unwrap()
+    }
+
+    fn phrase(&self, key: PhraseKey) -> Option<String> {
+        if key.is_plural() {
+            return self.plural(key, PluralCategory::Other);
+        }
+        self.get(key.name()).map(|text| text.to_string())
+    }
+
+    fn phrase_in(&self, key: PhraseKey, register: Register) -> Option<String> {
+        self.get(&register_name(key.name(), register))
+            .map(|text| text.to_string())
+    }
+
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        if !key.is_plural() {
+            return if category == PluralCategory::Other {
+                self.phrase(key)
+            } else {
+                None
+            };
+        }
+        self.get(&plural_name(key, category))
+            .map(|text| text.to_string())
+    }
+}
+
+// `EN`, `JA`, ... for each catalog file, and `ALL` listing them.
+include!(concat!(env!("OUT_DIR"), "/builtin.rs"));
@@ -0,0 +1,288 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use builtin;
+use fluent::Diagnostic;
+#[cfg(feature = "std")]
+use fluent::{self, Bundle};
+use locale::{Locale, ParseLocaleError};
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::{self, PluralCategory};
+use pseudo::Pseudo;
+use register::{register_name, Register};
+use syntax::{self, SyntaxError};
+
+// The file extension `Catalogs::load_dir` picks up.
+pub const EXTENSION: &str = "catalog";
+
+// The phrases for one locale, keyed by `module.function` name.
+//
+// On disk a catalog is a small INI-like file named after its locale
+// (`ja.catalog`, `ja-JP.catalog`):
+//
+//     # Japanese
+//     [greetings]
+//     hello = こんにちは
+//
+//     [farewells]
+//     goodbye = さようなら
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    locale: Locale,
+    entries: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Catalog {
+        Catalog {
+            locale,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    // Snapshots every phrase a compiled-in phrasebook knows. Plural phrases
+    // are stored as one `key.category` entry per form, register-specific
+    // forms as `key@register`.
+    pub fn from_phrasebook(book: &dyn Phrasebook) -> Catalog {
+        let locale = book.locale();
+        let mut catalog = Catalog::new(locale.clone());
+        for &key in PhraseKey::all() {
+            if !key.is_plural() {
+                if let Some(text) = book.phrase(key) {
+                    catalog.insert(key.name(), &text);
+                }
+                for &register in Register::all() {
+                    if let Some(text) = book.phrase_in(key, register) {
+                        catalog.insert(&register_name(key.name(), register), &text);
+                    }
+                }
+                continue;
+            }
+            for &category in plural::categories(&locale) {
+                if let Some(text) = book.plural(key, category) {
+                    catalog.insert(&plural_name(key, category), &text);
+                }
+            }
+        }
+        catalog
+    }
+
+    pub fn parse(locale: Locale, source: &str) -> Result<Catalog, CatalogError> {
+        let mut catalog = Catalog::new(locale);
+        for entry in syntax::parse(source).map_err(CatalogError::Syntax)? {
+            catalog.entries.insert(entry.key, entry.value);
+        }
+        Ok(catalog)
+    }
+
+    // Loads a catalog file, taking the locale from its file stem.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Catalog, CatalogError> {
+        let path = path.as_ref();
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        let locale = stem.parse().map_err(CatalogError::Locale)?;
+        let source = fs::read_to_string(path).map_err(CatalogError::Io)?;
+        if path.extension().and_then(|ext| ext.to_str()) == Some(fluent::EXTENSION) {
+            return Bundle::parse(locale, &source)
+                .and_then(|bundle| bundle.to_catalog())
+                .map_err(CatalogError::Fluent);
+        }
+        Catalog::parse(locale, &source)
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|text| text.as_str())
+    }
+
+    pub fn insert(&mut self, key: &str, text: &str) {
+        self.entries.insert(key.to_string(), text.to_string());
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(|key| key.as_str())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, text)| (key.as_str(), text.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Phrasebook for Catalog {
+    fn locale(&self) -> Locale {
+        self.locale.clone()
+    }
+
+    fn phrase(&self, key: PhraseKey) -> Option<String> {
+        if key.is_plural() {
+            return self.plural(key, PluralCategory::Other);
+        }
+        self.get(key.name()).map(|text| text.to_string())
+    }
+
+    fn phrase_in(&self, key: PhraseKey, register: Register) -> Option<String> {
+        self.get(&register_name(key.name(), register))
+            .map(|text| text.to_string())
+    }
+
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        if !key.is_plural() {
+            return if category == PluralCategory::Other {
+                self.phrase(key)
+            } else {
+                None
+            };
+        }
+        self.get(&plural_name(key, category))
+            .map(|text| text.to_string())
+    }
+}
+
+// The catalog entry holding one plural form, e.g. `farewells.count.one`.
+pub fn plural_name(key: PhraseKey, category: PluralCategory) -> String {
+    format!("{}.{}", key.name(), category.name())
+}
+
+// Writes the catalog back out in the on-disk format, one section per module.
+impl fmt::Display for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# {}", self.locale)?;
+        let mut section = None;
+        for (key, text) in &self.entries {
+            let (module, name) = match key.rfind('.') {
+                Some(at) => (&key[..at], &key[at + 1..]),
+                None => ("", key.as_str()),
+            };
+            if section != Some(module) {
+                writeln!(f)?;
+                if !module.is_empty() {
+                    writeln!(f, "[{}]", module)?;
+                }
+                section = Some(module);
+            }
+            writeln!(f, "{} = {}", name, text)?;
+        }
+        Ok(())
+    }
+}
+
+// Every locale's catalog, starting from the compiled-in ones.
+#[derive(Debug, Clone, Default)]
+pub struct Catalogs {
+    catalogs: BTreeMap<Locale, Catalog>,
+}
+
+impl Catalogs {
+    pub fn new() -> Catalogs {
+        Catalogs::default()
+    }
+
+    pub fn builtin() -> Catalogs {
+        let mut catalogs = Catalogs::new();
+        for &book in builtin::ALL {
+            catalogs.insert(Catalog::from_phrasebook(book));
+        }
+        for pseudo in Pseudo::all() {
+            catalogs.insert(Catalog::from_phrasebook(pseudo));
+        }
+        catalogs
+    }
+
+    // Adds `catalog`, replacing any catalog already loaded for its locale.
+    pub fn insert(&mut self, catalog: Catalog) {
+        self.catalogs.insert(catalog.locale.clone(), catalog);
+    }
+
+    // Loads every `*.catalog` and `*.ftl` file in `dir` on top of the
+    // current set.
+    #[cfg(feature = "std")]
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<(), CatalogError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir).map_err(CatalogError::Io)? {
+            let path = entry.map_err(CatalogError::Io)?.path();
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            if extension == Some(EXTENSION) || extension == Some(fluent::EXTENSION) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        for path in paths {
+            self.insert(Catalog::load(&path)?);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, locale: &Locale) -> Option<&Catalog> {
+        self.catalogs.get(locale)
+    }
+
+    pub fn locales(&self) -> impl Iterator<Item = &Locale> {
+        self.catalogs.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Catalog> {
+        self.catalogs.values()
+    }
+}
+
+#[derive(Debug)]
+pub enum CatalogError {
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    Locale(ParseLocaleError),
+    Syntax(SyntaxError),
+    Fluent(Vec<Diagnostic>),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "std")]
+            CatalogError::Io(ref err) => write!(f, "could not read catalog: {}", err),
+            CatalogError::Locale(ref err) => {
+                write!(f, "catalog file name is not a locale: {}", err)
+            }
+            CatalogError::Syntax(ref err) => err.fmt(f),
+            CatalogError::Fluent(ref diagnostics) => {
+                let lines: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+impl Error for CatalogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            #[cfg(feature = "std")]
+            CatalogError::Io(ref err) => Some(err),
+            CatalogError::Locale(ref err) => Some(err),
+            CatalogError::Syntax(_) | CatalogError::Fluent(_) => None,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::sync::{OnceLock, RwLock};
+
+use fallback::Resolver;
+use locale::Locale;
+use message::Args;
+use negotiate;
+use phrasebook::PhraseKey;
+
+// The ambient locale that `phrase!` from the `phrases_macros` crate formats
+// phrases for, so call sites need not pass one around. A locale set for
+// the current thread with `with_locale` wins over the process-wide one
+// from `set_locale`, which wins over LC_ALL/LC_MESSAGES/LANG; failing all
+// three, phrases are English.
+thread_local! {
+    static SCOPED: RefCell<Option<Locale>> = const { RefCell::new(None) };
+}
+
+static DEFAULT: RwLock<Option<Locale>> = RwLock::new(None);
+
+// The locale of the environment, read on first use.
+static FROM_ENV: OnceLock<Option<Locale>> = OnceLock::new();
+
+// Sets the locale for every thread without one of its own, e.g. from a
+// user preference read at startup.
+pub fn set_locale(locale: Locale) {
+    *DEFAULT.write().unwrap_or_else(|err| err.into_inner()) = Some(locale);
+}
+
+// Runs `f` with `locale` as this thread's ambient locale, e.g. for the
+// duration of one request. Scopes nest; the previous locale is restored
+// afterwards.
+pub fn with_locale<T, F: FnOnce() -> T>(locale: Locale, f: F) -> T {
+    // Restores the outer locale even if `f` panics.
+    struct Restore(Option<Locale>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = outer);
+        }
+    }
+
+    let _restore = Restore(SCOPED.with(|scoped| scoped.replace(Some(locale))));
+    f()
+}
+
+pub fn locale() -> Locale {
+    SCOPED
+        .with(|scoped| scoped.borrow().clone())
+        .or_else(|| {
+            DEFAULT
+                .read()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone()
+        })
+        .or_else(|| FROM_ENV.get_or_init(negotiate::from_env).clone())
+        .unwrap_or_else(|| Locale::new("en"))
+}
+
+// Formats `key` for the ambient locale, falling back through its language
+// to English. `phrase!` has checked `args` against the reference catalog,
+// so a translation that will not format with them is passed over for the
+// English phrase, and only a phrase missing even in English yields its key.
+pub fn format(key: PhraseKey, args: &Args) -> String {
+    let resolver = Resolver::new();
+    resolver
+        .format(&locale(), key, args)
+        .or_else(|_| resolver.format(resolver.default_locale(), key, args))
+        .map(|resolved| resolved.text)
+        .unwrap_or_else(|_| key.name().to_string())
+}
+
+// Formats the form of a count-dependent phrase for `count` like `format`.
+pub fn format_count(key: PhraseKey, count: u64, args: &Args) -> String {
+    let resolver = Resolver::new();
+    resolver
+        .format_count(&locale(), key, count, args)
+        .or_else(|_| resolver.format_count(resolver.default_locale(), key, count, args))
+        .map(|resolved| resolved.text)
+        .unwrap_or_else(|_| key.name().to_string())
+}
@@ -0,0 +1,381 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::{self, FromStr};
+
+use locale::Locale;
+use pseudo::Pseudo;
+use time::Date;
+
+// How a locale writes numbers, percentages, amounts of money and dates,
+// after CLDR. `#` in a pattern stands for the digits and `¤` for the
+// currency symbol; date patterns use `y`, `M`, `MM`, `MMMM`, `d` and `dd`,
+// with literal letters quoted as in `d 'de' MMMM`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Conventions {
+    pub decimal: char,
+    // Empty for locales that do not group digits.
+    pub group: &'static str,
+    // Digits the integer part needs beyond the first group before it is
+    // grouped at all: Spanish writes 1234 but 12.345.
+    pub min_grouping: usize,
+    pub percent: &'static str,
+    pub currency: &'static str,
+    pub short_date: &'static str,
+    pub long_date: &'static str,
+    pub months: &'static [&'static str; 12],
+    // Symbols by ISO 4217 code; other currencies are written by their code.
+    pub symbols: &'static [(&'static str, &'static str)],
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DateStyle {
+    // Numeric: 1/31/2026, 31.01.2026, 2026/01/31.
+    #[default]
+    Short,
+    // With the month spelled out: January 31, 2026, 31. Januar 2026.
+    Long,
+}
+
+// An ISO 4217 currency code such as `EUR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency {
+    code: [u8; 3],
+}
+
+impl Currency {
+    pub fn code(&self) -> &str {
+        str::from_utf8(&self.code).unwrap_or("XXX")
+    }
+
+    // Minor-unit digits amounts are written with: none for the yen and
+    // the won, two for most currencies.
+    pub fn digits(&self) -> usize {
+        match self.code() {
+            "JPY" | "KRW" | "VND" | "CLP" | "ISK" => 0,
+            _ => 2,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Currency, String> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 3 || !bytes.iter().all(|byte| byte.is_ascii_alphabetic()) {
+            return Err(format!("invalid currency code `{}`", code));
+        }
+        let mut currency = Currency { code: [0; 3] };
+        for (slot, byte) in currency.code.iter_mut().zip(bytes) {
+            *slot = byte.to_ascii_uppercase();
+        }
+        Ok(currency)
+    }
+}
+
+const ENGLISH_MONTHS: &[&str; 12] = &[
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const NUMBERED_MONTHS: &[&str; 12] = &[
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+
+// Locale-neutral forms, for locales without data of their own and for
+// formatting without a locale: 1234.5, EUR 1234.50, 2026-01-31.
+pub const ROOT: Conventions = Conventions {
+    decimal: '.',
+    group: "",
+    min_grouping: 1,
+    percent: "#%",
+    currency: "¤\u{A0}#",
+    short_date: "y-MM-dd",
+    long_date: "y-MM-dd",
+    months: ENGLISH_MONTHS,
+    symbols: &[],
+};
+
+pub const ENGLISH: Conventions = Conventions {
+    decimal: '.',
+    group: ",",
+    min_grouping: 1,
+    percent: "#%",
+    currency: "¤#",
+    short_date: "M/d/y",
+    long_date: "MMMM d, y",
+    months: ENGLISH_MONTHS,
+    symbols: &[
+        ("USD", "$"),
+        ("EUR", "€"),
+        ("GBP", "£"),
+        ("JPY", "¥"),
+        ("CNY", "CN¥"),
+    ],
+};
+
+pub const GERMAN: Conventions = Conventions {
+    decimal: ',',
+    group: ".",
+    min_grouping: 1,
+    percent: "#\u{A0}%",
+    currency: "#\u{A0}¤",
+    short_date: "dd.MM.y",
+    long_date: "d. MMMM y",
+    months: &[
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    symbols: &[
+        ("EUR", "€"),
+        ("USD", "$"),
+        ("GBP", "£"),
+        ("JPY", "¥"),
+        ("CNY", "CN¥"),
+    ],
+};
+
+pub const SPANISH: Conventions = Conventions {
+    decimal: ',',
+    group: ".",
+    min_grouping: 2,
+    percent: "#\u{A0}%",
+    currency: "#\u{A0}¤",
+    short_date: "d/M/y",
+    long_date: "d 'de' MMMM 'de' y",
+    months: &[
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    symbols: &[("EUR", "€"), ("USD", "US$"), ("JPY", "JPY"), ("CNY", "CNY")],
+};
+
+pub const FRENCH: Conventions = Conventions {
+    decimal: ',',
+    group: "\u{202F}",
+    min_grouping: 1,
+    percent: "#\u{202F}%",
+    currency: "#\u{A0}¤",
+    short_date: "dd/MM/y",
+    long_date: "d MMMM y",
+    months: &[
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    symbols: &[("EUR", "€"), ("USD", "$US"), ("GBP", "£GB")],
+};
+
+pub const JAPANESE: Conventions = Conventions {
+    decimal: '.',
+    group: ",",
+    min_grouping: 1,
+    percent: "#%",
+    currency: "¤#",
+    short_date: "y/MM/dd",
+    long_date: "y年M月d日",
+    months: NUMBERED_MONTHS,
+    symbols: &[
+        ("JPY", "￥"),
+        ("USD", "$"),
+        ("EUR", "€"),
+        ("GBP", "£"),
+        ("CNY", "元"),
+    ],
+};
+
+pub const CHINESE: Conventions = Conventions {
+    decimal: '.',
+    group: ",",
+    min_grouping: 1,
+    percent: "#%",
+    currency: "¤#",
+    short_date: "y/M/d",
+    long_date: "y年M月d日",
+    months: NUMBERED_MONTHS,
+    symbols: &[
+        ("CNY", "¥"),
+        ("USD", "US$"),
+        ("EUR", "€"),
+        ("GBP", "£"),
+        ("JPY", "JP¥"),
+    ],
+};
+
+// The conventions for `locale`'s language. The pseudo-locales follow
+// English, whose phrases they are made from.
+pub fn of(locale: &Locale) -> &'static Conventions {
+    if Pseudo::from_locale(locale).is_some() {
+        return &ENGLISH;
+    }
+    match locale.language() {
+        "en" => &ENGLISH,
+        "de" => &GERMAN,
+        "es" => &SPANISH,
+        "fr" => &FRENCH,
+        "ja" => &JAPANESE,
+        "zh" => &CHINESE,
+        _ => &ROOT,
+    }
+}
+
+// Numbers print with up to this many fraction digits, as in CLDR.
+const MAX_FRACTION_DIGITS: usize = 3;
+
+impl Conventions {
+    // `1,234.5` in English, `1.234,5` in German.
+    pub fn number(&self, value: f64) -> String {
+        self.signed(value, 0, MAX_FRACTION_DIGITS, "#", "")
+    }
+
+    // `value` as a percentage, so 0.25 is `25%` in English and `25 %` in
+    // German.
+    pub fn percent(&self, value: f64) -> String {
+        self.signed(value * 100.0, 0, 0, self.percent, "")
+    }
+
+    // `amount` of `currency` with its minor-unit digits: `$1,234.50`,
+    // `1.234,50 €`, `￥1,235`.
+    pub fn currency(&self, amount: f64, currency: &Currency) -> String {
+        let symbol = self
+            .symbols
+            .iter()
+            .find(|symbol| symbol.0 == currency.code())
+            .map_or(currency.code(), |symbol| symbol.1);
+        // A code written before the digits needs a space: `CHF 12.00`.
+        let spaced;
+        let pattern = if self.currency.starts_with("¤#")
+            && symbol.ends_with(|c: char| c.is_ascii_alphabetic())
+        {
+            spaced = self.currency.replacen("¤#", "¤\u{A0}#", 1);
+            &spaced
+        } else {
+            self.currency
+        };
+        let digits = currency.digits();
+        self.signed(amount, digits, digits, pattern, symbol)
+    }
+
+    pub fn date(&self, date: Date, style: DateStyle) -> String {
+        let pattern = match style {
+            DateStyle::Short => self.short_date,
+            DateStyle::Long => self.long_date,
+        };
+        let mut out = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                out.extend(chars.by_ref().take_while(|&c| c != '\''));
+                continue;
+            }
+            if !c.is_ascii_alphabetic() {
+                out.push(c);
+                continue;
+            }
+            let mut run = 1;
+            while chars.next_if_eq(&c).is_some() {
+                run += 1;
+            }
+            match (c, run) {
+                ('y', _) => out.push_str(&date.year().to_string()),
+                ('M', 4) => out.push_str(self.months[usize::from(date.month()) - 1]),
+                ('M', 2) => out.push_str(&format!("{:02}", date.month())),
+                ('M', _) => out.push_str(&date.month().to_string()),
+                ('d', 2) => out.push_str(&format!("{:02}", date.day())),
+                ('d', _) => out.push_str(&date.day().to_string()),
+                _ => out.extend((0..run).map(|_| c)),
+            }
+        }
+        out
+    }
+
+    // Writes `value` rounded to between `min` and `max` fraction digits
+    // into `pattern`, with a leading minus sign when negative.
+    fn signed(&self, value: f64, min: usize, max: usize, pattern: &str, symbol: &str) -> String {
+        if value.is_nan() {
+            return "NaN".to_string();
+        }
+        let digits = if value.is_infinite() {
+            "∞".to_string()
+        } else {
+            self.digits(value.abs(), min, max)
+        };
+        let negative = value < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0');
+        let text = pattern.replace('#', &digits).replace('¤', symbol);
+        if negative || value == f64::NEG_INFINITY {
+            format!("-{}", text)
+        } else {
+            text
+        }
+    }
+
+    fn digits(&self, value: f64, min: usize, max: usize) -> String {
+        let rounded = format!("{:.*}", max, value);
+        let (integer, fraction) = match rounded.find('.') {
+            Some(at) => (&rounded[..at], &rounded[at + 1..]),
+            None => (rounded.as_str(), ""),
+        };
+        let keep = fraction.trim_end_matches('0').len().max(min);
+        let fraction = &fraction[..keep];
+
+        let mut out = String::new();
+        let grouped = !self.group.is_empty() && integer.len() >= 3 + self.min_grouping;
+        for (i, digit) in integer.chars().enumerate() {
+            let remaining = integer.len() - i;
+            if grouped && i > 0 && remaining % 3 == 0 {
+                out.push_str(self.group);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+}
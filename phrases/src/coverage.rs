@@ -0,0 +1,179 @@
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use catalog::{self, Catalog, Catalogs};
+use locale::Locale;
+use message;
+use phrasebook::PhraseKey;
+use plural::{self, PluralCategory};
+use pseudo::Pseudo;
+use register::split_register;
+
+// A key whose translation uses different placeholders than the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceholderMismatch {
+    pub key: String,
+    pub expected: Vec<String>,
+    pub found: Vec<String>,
+}
+
+// How completely one catalog translates the reference catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub locale: Locale,
+    pub reference: Locale,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+    pub mismatched: Vec<PlaceholderMismatch>,
+}
+
+impl Coverage {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} (reference {}): {} missing, {} extra, {} placeholder mismatches",
+            self.locale,
+            self.reference,
+            self.missing.len(),
+            self.extra.len(),
+            self.mismatched.len()
+        )?;
+        for key in &self.missing {
+            writeln!(f, "  missing {}", key)?;
+        }
+        for key in &self.extra {
+            writeln!(f, "  extra {}", key)?;
+        }
+        for mismatch in &self.mismatched {
+            writeln!(
+                f,
+                "  placeholders {}: expected {}, found {}",
+                mismatch.key,
+                braced(&mismatch.expected),
+                braced(&mismatch.found)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn braced(names: &[String]) -> String {
+    if names.is_empty() {
+        return "none".to_string();
+    }
+    let names: Vec<String> = names.iter().map(|name| format!("{{{}}}", name)).collect();
+    names.join(", ")
+}
+
+// Splits a plural entry such as `farewells.count.one` into its key and
+// category.
+fn plural_entry(name: &str) -> Option<(PhraseKey, PluralCategory)> {
+    let at = name.rfind('.')?;
+    let key = PhraseKey::from_name(&name[..at])?;
+    let category = PluralCategory::from_name(&name[at + 1..])?;
+    if key.is_plural() {
+        Some((key, category))
+    } else {
+        None
+    }
+}
+
+// The entry names a catalog for `locale` should have, given the reference.
+// Plural phrases need the forms `locale`'s own plural rules call for.
+fn expected_names(reference: &Catalog, locale: &Locale) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for name in reference.keys() {
+        if split_register(name).1.is_some() {
+            continue;
+        }
+        match plural_entry(name) {
+            Some((key, _)) => {
+                for &category in plural::categories(locale) {
+                    names.insert(catalog::plural_name(key, category));
+                }
+            }
+            None => {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+// The reference text a translated entry should match placeholders with.
+// Plural forms are compared against the reference's `other` form.
+pub fn reference_text<'a>(reference: &'a Catalog, name: &str) -> Option<&'a str> {
+    match plural_entry(name) {
+        Some((key, _)) => reference.get(&catalog::plural_name(key, PluralCategory::Other)),
+        None => reference.get(name),
+    }
+}
+
+fn sorted_placeholders(text: &str) -> Vec<String> {
+    let mut names = message::placeholders(text).unwrap_or_default();
+    names.sort();
+    names
+}
+
+// Diffs `catalog` against `reference`. Register-specific forms are never
+// required, but must translate a reference key and match its placeholders.
+pub fn compare(reference: &Catalog, catalog: &Catalog) -> Coverage {
+    let expected = expected_names(reference, catalog.locale());
+    let mut present = BTreeSet::new();
+    let mut extra = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for (name, text) in catalog.entries() {
+        let (base, register) = split_register(name);
+        if !expected.contains(base) {
+            extra.push(name.to_string());
+            continue;
+        }
+        if register.is_none() {
+            present.insert(base.to_string());
+        }
+
+        let expected = reference_text(reference, base)
+            .map(sorted_placeholders)
+            .unwrap_or_default();
+        let found = sorted_placeholders(text);
+        if expected != found {
+            mismatched.push(PlaceholderMismatch {
+                key: name.to_string(),
+                expected,
+                found,
+            });
+        }
+    }
+
+    Coverage {
+        locale: catalog.locale().clone(),
+        reference: reference.locale().clone(),
+        missing: expected.difference(&present).cloned().collect(),
+        extra,
+        mismatched,
+    }
+}
+
+// Diffs every catalog other than the reference against it, leaving out
+// the pseudo-locales, which are generated from English and always complete.
+// Returns `None` if there is no catalog for `reference`.
+pub fn report(catalogs: &Catalogs, reference: &Locale) -> Option<Vec<Coverage>> {
+    let reference = catalogs.get(reference)?;
+    Some(
+        catalogs
+            .iter()
+            .filter(|catalog| catalog.locale() != reference.locale())
+            .filter(|catalog| Pseudo::from_locale(catalog.locale()).is_none())
+            .map(|catalog| compare(reference, catalog))
+            .collect(),
+    )
+}
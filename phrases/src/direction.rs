@@ -0,0 +1,87 @@
+use alloc::string::String;
+use core::fmt;
+
+use locale::Locale;
+
+// The direction a locale's text runs in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+// Languages written right to left: Arabic, Hebrew, Persian, Urdu, Pashto,
+// Yiddish, Dhivehi, Sorani Kurdish, Sindhi and Uyghur.
+const RIGHT_TO_LEFT: &[&str] = &[
+    "ar", "he", "iw", "fa", "ur", "ps", "yi", "dv", "ckb", "sd", "ug",
+];
+
+// Wraps an argument so its direction cannot leak into the surrounding text.
+pub const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+pub const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+impl Direction {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Direction::LeftToRight => "ltr",
+            Direction::RightToLeft => "rtl",
+        }
+    }
+
+    pub fn is_right_to_left(&self) -> bool {
+        *self == Direction::RightToLeft
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+pub fn of(locale: &Locale) -> Direction {
+    if RIGHT_TO_LEFT.contains(&locale.language()) {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    }
+}
+
+// True for characters of the right-to-left scripts: Hebrew, Arabic, Syriac,
+// Thaana, N'Ko and their presentation forms and historic relatives.
+pub fn is_right_to_left(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+// The direction of the first strongly directional character in `text`, as
+// the Unicode bidi algorithm would pick it, if `text` has one.
+pub fn of_text(text: &str) -> Option<Direction> {
+    text.chars().find_map(|c| {
+        if is_right_to_left(c) {
+            Some(Direction::RightToLeft)
+        } else if c.is_alphabetic() {
+            Some(Direction::LeftToRight)
+        } else {
+            None
+        }
+    })
+}
+
+// Whether an argument interpolated into `direction` text needs isolating.
+// Right-to-left text isolates every argument; left-to-right text only those
+// that carry right-to-left characters, so plain Latin output is unchanged.
+pub fn needs_isolation(direction: Direction, argument: &str) -> bool {
+    direction.is_right_to_left() || argument.chars().any(is_right_to_left)
+}
+
+// `argument` between a first-strong isolate and its pop.
+pub fn isolate(argument: &str) -> String {
+    format!(
+        "{}{}{}",
+        FIRST_STRONG_ISOLATE, argument, POP_DIRECTIONAL_ISOLATE
+    )
+}
@@ -1,3 +1,16 @@
+use alloc::string::String;
+
+use phrasebook::PhraseKey;
+use plural::PluralCategory;
+
 pub fn goodbye() -> String {
-    "Goodbye.".to_string()
+    super::text(PhraseKey::Goodbye)
+}
+
+pub fn goodbye_to() -> String {
+    super::text(PhraseKey::GoodbyeTo)
+}
+
+pub fn farewells(category: PluralCategory) -> String {
+    super::plural_text(PhraseKey::Farewells, category)
 }
\ No newline at end of file
@@ -1,7 +1,15 @@
+use alloc::string::String;
+
+use phrasebook::PhraseKey;
+
 pub fn hello() -> String {
-    "Hello!".to_string()
+    super::text(PhraseKey::Hello)
 }
 
 pub fn hi() -> String {
-    "Hi~".to_string()
+    super::text(PhraseKey::Hi)
+}
+
+pub fn hello_to() -> String {
+    super::text(PhraseKey::HelloTo)
 }
\ No newline at end of file
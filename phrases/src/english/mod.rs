@@ -1,2 +1,38 @@
 pub mod greetings;
-pub mod farewells;
\ No newline at end of file
+pub mod farewells;
+
+use alloc::string::String;
+
+use builtin;
+use locale::Locale;
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::{self, PluralCategory};
+use register::Register;
+
+pub struct English;
+
+impl Phrasebook for English {
+    fn locale(&self) -> Locale {
+        builtin::EN.locale()
+    }
+
+    fn phrase(&self, key: PhraseKey) -> Option<String> {
+        builtin::EN.phrase(key)
+    }
+
+    fn phrase_in(&self, key: PhraseKey, register: Register) -> Option<String> {
+        builtin::EN.phrase_in(key, register)
+    }
+
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        builtin::EN.plural(key, category)
+    }
+}
+
+fn text(key: PhraseKey) -> String {
+    English.phrase(key).unwrap_or_default()
+}
+
+fn plural_text(key: PhraseKey, category: PluralCategory) -> String {
+    plural::select(&English, key, category).unwrap_or_default()
+}
@@ -0,0 +1,209 @@
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use catalog::Catalogs;
+use locale::Locale;
+use message::{self, Args, FormatError, Value};
+use negotiate::{self, Negotiated};
+use observe::{Event, Observer};
+use phrasebook::{PhraseKey, Phrasebook};
+use plural;
+use register::{self, Register};
+use time::{Clock, DayPeriod};
+
+// A phrase together with the locale whose phrasebook actually served it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    pub text: String,
+    pub requested: Locale,
+    pub locale: Locale,
+}
+
+impl Resolved {
+    // True when the requested language had no translation and the phrase
+    // came from a fallback language instead.
+    pub fn is_fallback(&self) -> bool {
+        self.locale.language() != self.requested.language()
+    }
+}
+
+// Resolves phrases through a fallback chain: the full tag (`ja-JP`), then
+// its language (`ja`), then the default locale (English unless configured).
+// It reads the built-in phrasebooks unless given catalogs to use instead.
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    default: Locale,
+    catalogs: Option<Catalogs>,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver::with_default(Locale::new("en"))
+    }
+
+    pub fn with_default(default: Locale) -> Resolver {
+        Resolver {
+            default,
+            catalogs: None,
+            observer: None,
+        }
+    }
+
+    pub fn with_catalogs(mut self, catalogs: Catalogs) -> Resolver {
+        self.catalogs = Some(catalogs);
+        self
+    }
+
+    // Reports every lookup, with where it was served from, to `observer`.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Resolver {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn catalogs(&self) -> Option<&Catalogs> {
+        self.catalogs.as_ref()
+    }
+
+    fn phrasebook(&self, locale: &Locale) -> Option<&dyn Phrasebook> {
+        match self.catalogs {
+            Some(ref catalogs) => catalogs
+                .get(locale)
+                .map(|catalog| catalog as &dyn Phrasebook),
+            None => ::phrasebook(locale),
+        }
+    }
+
+    pub fn default_locale(&self) -> &Locale {
+        &self.default
+    }
+
+    // Chooses the best locale this resolver has for an `Accept-Language`
+    // header, treating its wildcard as the default locale.
+    pub fn negotiate(&self, accept_language: &str) -> Option<Negotiated> {
+        let preferences = negotiate::parse_accept_language(accept_language);
+        match self.catalogs {
+            Some(ref catalogs) => {
+                negotiate::negotiate(&preferences, catalogs.locales(), &self.default)
+            }
+            None => negotiate::negotiate(&preferences, &::locales(), &self.default),
+        }
+    }
+
+    pub fn chain(&self, locale: &Locale) -> Vec<Locale> {
+        let mut chain = vec![locale.clone()];
+        if locale.region().is_some() {
+            chain.push(Locale::new(locale.language()));
+        }
+        if !chain.contains(&self.default) {
+            chain.push(self.default.clone());
+        }
+        chain
+    }
+
+    pub fn resolve(&self, locale: &Locale, key: PhraseKey) -> Option<Resolved> {
+        self.resolve_by(locale, key, |candidate| {
+            self.phrasebook(candidate).and_then(|book| book.phrase(key))
+        })
+    }
+
+    // Like `resolve`, preferring each locale's form for `register`.
+    pub fn resolve_with_register(
+        &self,
+        locale: &Locale,
+        key: PhraseKey,
+        register: Register,
+    ) -> Option<Resolved> {
+        self.resolve_by(locale, key, |candidate| {
+            self.phrasebook(candidate)
+                .and_then(|book| register::select(book, key, register))
+        })
+    }
+
+    // Resolves the greeting for the part of the day `clock` reports.
+    pub fn greeting(&self, locale: &Locale, clock: &dyn Clock) -> Option<Resolved> {
+        self.resolve(locale, DayPeriod::of(clock.now()).greeting())
+    }
+
+    // Resolves `key` and fills in its placeholders from `args`.
+    pub fn format(
+        &self,
+        locale: &Locale,
+        key: PhraseKey,
+        args: &Args,
+    ) -> Result<Resolved, FormatError> {
+        let mut resolved = self
+            .resolve(locale, key)
+            .ok_or_else(|| FormatError::MissingPhrase(key.name().to_string()))?;
+        resolved.text = message::format_localized(&resolved.text, args, &resolved.locale)?;
+        Ok(resolved)
+    }
+
+    // Resolves the form of a count-dependent phrase for `count`, trying
+    // each locale in the chain with its own plural rules.
+    pub fn format_count(
+        &self,
+        locale: &Locale,
+        key: PhraseKey,
+        count: u64,
+        args: &Args,
+    ) -> Result<Resolved, FormatError> {
+        let mut resolved = self
+            .resolve_by(locale, key, |candidate| {
+                self.phrasebook(candidate)
+                    .and_then(|book| plural::select(book, key, plural::category(candidate, count)))
+            })
+            .ok_or_else(|| FormatError::MissingPhrase(key.name().to_string()))?;
+        resolved.text = message::format_localized(
+            &resolved.text,
+            &args
+                .clone()
+                .with_value("count", Value::Number(count as f64)),
+            &resolved.locale,
+        )?;
+        Ok(resolved)
+    }
+
+    // Like `resolve`, but against catalogs loaded at runtime.
+    pub fn resolve_in(
+        &self,
+        catalogs: &Catalogs,
+        locale: &Locale,
+        key: PhraseKey,
+    ) -> Option<Resolved> {
+        self.resolve_by(locale, key, |candidate| {
+            catalogs
+                .get(candidate)
+                .and_then(|catalog| catalog.phrase(key))
+        })
+    }
+
+    fn resolve_by<F>(&self, locale: &Locale, key: PhraseKey, find: F) -> Option<Resolved>
+    where
+        F: Fn(&Locale) -> Option<String>,
+    {
+        let resolved = self
+            .chain(locale)
+            .into_iter()
+            .filter_map(|candidate| {
+                find(&candidate).map(|text| Resolved {
+                    text,
+                    requested: locale.clone(),
+                    locale: candidate,
+                })
+            })
+            .next();
+        if let Some(ref observer) = self.observer {
+            let served = resolved.as_ref().map(|resolved| &resolved.locale);
+            observer.observe(&Event::new(key, locale, served));
+        }
+        resolved
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Resolver {
+        Resolver::new()
+    }
+}
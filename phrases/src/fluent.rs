@@ -0,0 +1,1100 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use catalog::{plural_name, Catalog};
+use conventions;
+use direction;
+use locale::Locale;
+use message::Args;
+use phrasebook::PhraseKey;
+use plural::{self, PluralCategory};
+use register::{register_name, split_register, Register};
+
+// A subset of Mozilla's Fluent syntax: messages, terms, attributes,
+// variables, message and term references, string and number literals, and
+// select expressions. Phrase keys map to message ids with `-` in place of
+// `.` (`greetings.hello` is `greetings-hello`), register forms are
+// attributes, and plural phrases select on `$count`:
+//
+//     -brand = Phrases
+//     greetings-hello = Hello from { -brand }!
+//         .casual = Hey!
+//     farewells-farewells = { $count ->
+//         [one] { $count } farewell
+//        *[other] { $count } farewells
+//     }
+
+pub const EXTENSION: &str = "ftl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+// A syntax error or unresolved reference, located in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub position: Position,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(position: Position, message: &str) -> Diagnostic {
+        Diagnostic {
+            position,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Line zero marks errors that have no place in the source, such as
+        // formatting a message that does not exist.
+        if self.position.line == 0 {
+            return f.write_str(&self.message);
+        }
+        write!(
+            f,
+            "{}:{}: {}",
+            self.position.line, self.position.column, self.message
+        )
+    }
+}
+
+impl Error for Diagnostic {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+    Text(String),
+    Placeable(Expression, Position),
+}
+
+pub type Pattern = Vec<Element>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    String(String),
+    Number(f64),
+    Variable(String),
+    Message(String, Option<String>),
+    Term(String, Option<String>),
+    Select(Box<Expression>, Vec<Variant>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub key: String,
+    pub value: Pattern,
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub id: String,
+    pub term: bool,
+    pub value: Option<Pattern>,
+    pub attributes: Vec<(String, Pattern)>,
+    pub position: Position,
+}
+
+// Parses a resource, skipping entries with syntax errors the way Fluent
+// does. Every error is reported with its line and column.
+pub fn parse(source: &str) -> (Vec<Entry>, Vec<Diagnostic>) {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        at: 0,
+        line: 1,
+        column: 1,
+        nesting: 0,
+    };
+    let mut entries = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    loop {
+        parser.skip_blank_lines();
+        match parser.peek() {
+            None => break,
+            Some('#') => parser.skip_line(),
+            Some(_) => {
+                let start = parser.at;
+                match parser.entry() {
+                    Ok(entry) => entries.push(entry),
+                    Err(diagnostic) => {
+                        diagnostics.push(diagnostic);
+                        // An unclosed placeable can leave the parser at the
+                        // start of the next entry, which is not junk.
+                        if parser.at == start || !parser.at_entry_start() {
+                            parser.skip_junk();
+                        }
+                    }
+                }
+            }
+        }
+    }
+    (entries, diagnostics)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    at: usize,
+    line: usize,
+    column: usize,
+    // Placeables open around the current position.
+    nesting: usize,
+}
+
+// Placeables nested deeper than this are refused rather than parsed with
+// ever more stack.
+const MAX_NESTING: usize = 64;
+
+type Parse<T> = Result<T, Diagnostic>;
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.at).cloned()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.at + offset).cloned()
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.at += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error<T>(&self, message: &str) -> Parse<T> {
+        Err(Diagnostic::new(self.position(), message))
+    }
+
+    fn expect(&mut self, expected: char) -> Parse<()> {
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", expected))
+        }
+    }
+
+    fn skip_inline_space(&mut self) {
+        while self.peek() == Some(' ') || self.peek() == Some('\t') {
+            self.bump();
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn skip_blank_lines(&mut self) {
+        loop {
+            let mut offset = 0;
+            while self.peek_at(offset) == Some(' ')
+                || self.peek_at(offset) == Some('\t')
+                || self.peek_at(offset) == Some('\r')
+            {
+                offset += 1;
+            }
+            match self.peek_at(offset) {
+                Some('\n') => {
+                    for _ in 0..=offset {
+                        self.bump();
+                    }
+                }
+                None if offset > 0 => {
+                    for _ in 0..offset {
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    // Whether the parser sits in column one on something that opens an entry.
+    fn at_entry_start(&self) -> bool {
+        self.column == 1
+            && self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '-' || c == '#')
+    }
+
+    // Skips to the next line that starts in column one, where the next entry
+    // or comment can begin.
+    fn skip_junk(&mut self) {
+        loop {
+            self.skip_line();
+            match self.peek() {
+                None => break,
+                Some(c) if !c.is_whitespace() && c != '}' && c != '[' && c != '*' && c != '.' => {
+                    break
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // The number of spaces that indent the next line, if it continues the
+    // current pattern. Lines starting with `[`, `*`, `.` or `}` belong to
+    // the surrounding syntax instead.
+    fn continuation(&self) -> Option<usize> {
+        let mut offset = 0;
+        let mut indented = false;
+        loop {
+            match self.peek_at(offset) {
+                Some('\n') => {
+                    offset += 1;
+                    indented = false;
+                }
+                Some(' ') | Some('\t') | Some('\r') => {
+                    offset += 1;
+                    indented = true;
+                }
+                Some('[') | Some('*') | Some('.') | Some('}') | None => return None,
+                Some(_) if indented => return Some(offset),
+                Some(_) => return None,
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Parse<String> {
+        let mut id = String::new();
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {}
+            _ => return self.error("expected an identifier"),
+        }
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                id.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        Ok(id)
+    }
+
+    fn entry(&mut self) -> Parse<Entry> {
+        let position = self.position();
+        let term = self.peek() == Some('-');
+        if term {
+            self.bump();
+        }
+        let id = self.identifier()?;
+        self.skip_inline_space();
+        self.expect('=')?;
+        self.skip_inline_space();
+
+        let value = self.pattern()?;
+        let mut attributes = Vec::new();
+        loop {
+            let mut offset = 0;
+            while self.peek_at(offset).is_some_and(|c| c.is_whitespace()) {
+                offset += 1;
+            }
+            if offset == 0 || self.peek_at(offset) != Some('.') {
+                break;
+            }
+            self.skip_blank();
+            self.bump();
+            let name = self.identifier()?;
+            self.skip_inline_space();
+            self.expect('=')?;
+            self.skip_inline_space();
+            match self.pattern()? {
+                Some(pattern) => attributes.push((name, pattern)),
+                None => return self.error(&format!("attribute `.{}` has no value", name)),
+            }
+        }
+
+        if term && value.is_none() {
+            return Err(Diagnostic::new(
+                position,
+                &format!("term `-{}` has no value", id),
+            ));
+        }
+        if value.is_none() && attributes.is_empty() {
+            return Err(Diagnostic::new(
+                position,
+                &format!("message `{}` has no value", id),
+            ));
+        }
+        Ok(Entry {
+            id,
+            term,
+            value,
+            attributes,
+            position,
+        })
+    }
+
+    // A pattern runs to the end of the line and over any indented lines
+    // that continue it.
+    fn pattern(&mut self) -> Parse<Option<Pattern>> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            match self.peek() {
+                None => break,
+                Some('{') => {
+                    if !text.is_empty() {
+                        elements.push(Element::Text(text.clone()));
+                        text.clear();
+                    }
+                    let position = self.position();
+                    self.bump();
+                    elements.push(Element::Placeable(self.placeable(position)?, position));
+                }
+                Some('}') => return self.error("unbalanced `}`"),
+                Some('\n') | Some('\r') => match self.continuation() {
+                    Some(indent) => {
+                        for _ in 0..indent {
+                            self.bump();
+                        }
+                        if !text.is_empty() || !elements.is_empty() {
+                            text.push('\n');
+                        }
+                    }
+                    None => break,
+                },
+                Some(c) => {
+                    text.push(c);
+                    self.bump();
+                }
+            }
+        }
+
+        let trimmed = text.trim_end().to_string();
+        if !trimmed.is_empty() {
+            elements.push(Element::Text(trimmed));
+        }
+        Ok(if elements.is_empty() {
+            None
+        } else {
+            Some(elements)
+        })
+    }
+
+    // The rest of a placeable whose `{` at `opening` was just consumed.
+    fn placeable(&mut self, opening: Position) -> Parse<Expression> {
+        if self.nesting >= MAX_NESTING {
+            return Err(Diagnostic::new(opening, "placeables nested too deeply"));
+        }
+        self.nesting += 1;
+        let expression = self.placeable_body(opening);
+        self.nesting -= 1;
+        expression
+    }
+
+    fn placeable_body(&mut self, opening: Position) -> Parse<Expression> {
+        self.skip_blank();
+        if self.peek().is_none() {
+            return Err(Diagnostic::new(opening, "unclosed placeable"));
+        }
+        let expression = self.inline_expression()?;
+        self.skip_blank();
+
+        if self.peek() == Some('-') && self.peek_at(1) == Some('>') {
+            self.bump();
+            self.bump();
+            let variants = self.variants()?;
+            self.skip_blank();
+            self.close(opening)?;
+            return Ok(Expression::Select(Box::new(expression), variants));
+        }
+        self.close(opening)?;
+        Ok(expression)
+    }
+
+    // Consumes the `}` closing the placeable opened at `opening`. One
+    // missing at the end of the input or past the end of the line is
+    // reported at the `{`, not wherever the next entry happens to start.
+    fn close(&mut self, opening: Position) -> Parse<()> {
+        match self.peek() {
+            Some('}') => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) if self.line == opening.line => self.error("expected `}`"),
+            _ => Err(Diagnostic::new(opening, "unclosed placeable")),
+        }
+    }
+
+    fn inline_expression(&mut self) -> Parse<Expression> {
+        match self.peek() {
+            Some('"') => self.string_literal().map(Expression::String),
+            Some('$') => {
+                self.bump();
+                self.identifier().map(Expression::Variable)
+            }
+            Some('{') => {
+                let opening = self.position();
+                self.bump();
+                self.placeable(opening)
+            }
+            Some(c) if c.is_ascii_digit() => self.number_literal(),
+            Some('-') if self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) => {
+                self.number_literal()
+            }
+            Some('-') => {
+                self.bump();
+                let id = self.identifier()?;
+                let attribute = self.attribute_accessor()?;
+                self.reject_call()?;
+                Ok(Expression::Term(id, attribute))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let id = self.identifier()?;
+                let attribute = self.attribute_accessor()?;
+                self.reject_call()?;
+                Ok(Expression::Message(id, attribute))
+            }
+            _ => self.error("expected an expression"),
+        }
+    }
+
+    fn attribute_accessor(&mut self) -> Parse<Option<String>> {
+        if self.peek() != Some('.') {
+            return Ok(None);
+        }
+        self.bump();
+        self.identifier().map(Some)
+    }
+
+    fn reject_call(&mut self) -> Parse<()> {
+        if self.peek() == Some('(') {
+            return self.error("functions and term arguments are not supported");
+        }
+        Ok(())
+    }
+
+    fn string_literal(&mut self) -> Parse<String> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('u') => {
+                        let digits: String = (0..4).filter_map(|_| self.bump()).collect();
+                        match u32::from_str_radix(&digits, 16)
+                            .ok()
+                            .and_then(::core::char::from_u32)
+                        {
+                            Some(c) => value.push(c),
+                            None => return self.error("invalid unicode escape"),
+                        }
+                    }
+                    _ => return self.error("unknown escape sequence"),
+                },
+                Some('\n') | None => return self.error("unterminated string literal"),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn number_literal(&mut self) -> Parse<Expression> {
+        let position = self.position();
+        let mut literal = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || c == '.' || (c == '-' && literal.is_empty()) {
+                literal.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        literal
+            .parse()
+            .map(Expression::Number)
+            .map_err(|_| Diagnostic::new(position, "invalid number literal"))
+    }
+
+    fn variants(&mut self) -> Parse<Vec<Variant>> {
+        let position = self.position();
+        let mut variants: Vec<Variant> = Vec::new();
+        loop {
+            self.skip_blank();
+            let default = self.peek() == Some('*');
+            if default {
+                self.bump();
+            }
+            if self.peek() != Some('[') {
+                break;
+            }
+            self.bump();
+            self.skip_blank();
+            let key = match self.peek() {
+                Some(c) if c.is_ascii_digit() || c == '-' => match self.number_literal()? {
+                    Expression::Number(number) => number.to_string(),
+                    _ => unreachable!(),
+                },
+                _ => self.identifier()?,
+            };
+            self.skip_blank();
+            self.expect(']')?;
+            self.skip_inline_space();
+            let value = self.pattern()?.unwrap_or_default();
+            variants.push(Variant {
+                key,
+                value,
+                default,
+            });
+        }
+
+        match variants.iter().filter(|variant| variant.default).count() {
+            1 => Ok(variants),
+            0 => Err(Diagnostic::new(
+                position,
+                "select expression needs a default `*[...]` variant",
+            )),
+            _ => Err(Diagnostic::new(
+                position,
+                "select expression has more than one default variant",
+            )),
+        }
+    }
+}
+
+// The Fluent message id for a phrase key.
+pub fn message_id(key: PhraseKey) -> String {
+    key.name().replace('.', "-")
+}
+
+// Messages and terms for one locale, ready to format.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    locale: Locale,
+    messages: BTreeMap<String, Entry>,
+    terms: BTreeMap<String, Entry>,
+}
+
+impl Bundle {
+    pub fn new(locale: Locale) -> Bundle {
+        Bundle {
+            locale,
+            messages: BTreeMap::new(),
+            terms: BTreeMap::new(),
+        }
+    }
+
+    // Parses `source` into a bundle, failing with every syntax error and
+    // unresolved reference it contains.
+    pub fn parse(locale: Locale, source: &str) -> Result<Bundle, Vec<Diagnostic>> {
+        let (entries, mut diagnostics) = parse(source);
+        let mut bundle = Bundle::new(locale);
+        diagnostics.extend(bundle.add_entries(entries));
+        diagnostics.extend(bundle.check());
+        if diagnostics.is_empty() {
+            Ok(bundle)
+        } else {
+            diagnostics.sort_by_key(|diagnostic| diagnostic.position);
+            Err(diagnostics)
+        }
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    // Adds entries, reporting ids defined more than once.
+    pub fn add_entries(&mut self, entries: Vec<Entry>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entry in entries {
+            let (map, name) = if entry.term {
+                (&mut self.terms, format!("-{}", entry.id))
+            } else {
+                (&mut self.messages, entry.id.clone())
+            };
+            if map.contains_key(&entry.id) {
+                diagnostics.push(Diagnostic::new(
+                    entry.position,
+                    &format!("`{}` is already defined", name),
+                ));
+                continue;
+            }
+            map.insert(entry.id.clone(), entry);
+        }
+        diagnostics
+    }
+
+    pub fn has_message(&self, id: &str) -> bool {
+        self.messages.contains_key(id)
+    }
+
+    // Every reference to a message, term or attribute that does not exist.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entry in self.messages.values().chain(self.terms.values()) {
+            let patterns = entry
+                .value
+                .iter()
+                .chain(entry.attributes.iter().map(|attribute| &attribute.1));
+            for pattern in patterns {
+                self.check_pattern(pattern, &mut diagnostics);
+            }
+        }
+        diagnostics
+    }
+
+    fn check_pattern(&self, pattern: &Pattern, diagnostics: &mut Vec<Diagnostic>) {
+        for element in pattern {
+            if let Element::Placeable(ref expression, position) = *element {
+                self.check_expression(expression, position, diagnostics);
+            }
+        }
+    }
+
+    fn check_expression(
+        &self,
+        expression: &Expression,
+        position: Position,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        match *expression {
+            Expression::Message(ref id, ref attribute) => {
+                if let Err(message) = self.target(&self.messages, id, attribute.as_ref(), "") {
+                    diagnostics.push(Diagnostic::new(position, &message));
+                }
+            }
+            Expression::Term(ref id, ref attribute) => {
+                if let Err(message) = self.target(&self.terms, id, attribute.as_ref(), "-") {
+                    diagnostics.push(Diagnostic::new(position, &message));
+                }
+            }
+            Expression::Select(ref selector, ref variants) => {
+                self.check_expression(selector, position, diagnostics);
+                for variant in variants {
+                    self.check_pattern(&variant.value, diagnostics);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn target<'a>(
+        &self,
+        map: &'a BTreeMap<String, Entry>,
+        id: &str,
+        attribute: Option<&String>,
+        sigil: &str,
+    ) -> Result<&'a Pattern, String> {
+        let entry = map
+            .get(id)
+            .ok_or_else(|| format!("unknown {} `{}{}`", kind(sigil), sigil, id))?;
+        match attribute {
+            Some(name) => entry
+                .attributes
+                .iter()
+                .find(|attribute| attribute.0 == *name)
+                .map(|attribute| &attribute.1)
+                .ok_or_else(|| format!("`{}{}` has no attribute `.{}`", sigil, id, name)),
+            None => entry
+                .value
+                .as_ref()
+                .ok_or_else(|| format!("`{}{}` has no value", sigil, id)),
+        }
+    }
+
+    // Formats message `id`, or its attribute when `id` is `message.attribute`.
+    pub fn format(&self, id: &str, args: &Args) -> Result<String, Vec<Diagnostic>> {
+        let (id, attribute) = match id.find('.') {
+            Some(at) => (&id[..at], Some(id[at + 1..].to_string())),
+            None => (id, None),
+        };
+        let pattern = self
+            .target(&self.messages, id, attribute.as_ref(), "")
+            .map_err(|message| vec![Diagnostic::new(Position { line: 0, column: 0 }, &message)])?;
+
+        let mut scope = Scope {
+            bundle: self,
+            args,
+            depth: 0,
+            diagnostics: Vec::new(),
+        };
+        let mut out = String::new();
+        scope.pattern(pattern, &mut out);
+        if scope.diagnostics.is_empty() {
+            Ok(out)
+        } else {
+            Err(scope.diagnostics)
+        }
+    }
+
+    // Flattens the messages for phrase keys into a catalog. Attributes named
+    // after a register become register forms, a select on `$count` in a
+    // plural phrase becomes its plural forms and other selects on variables
+    // become catalog selects; selects on anything else are reported.
+    pub fn to_catalog(&self) -> Result<Catalog, Vec<Diagnostic>> {
+        let mut catalog = Catalog::new(self.locale.clone());
+        let mut diagnostics = Vec::new();
+
+        for &key in PhraseKey::all() {
+            let entry = match self.messages.get(&message_id(key)) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            if let Some(ref value) = entry.value {
+                if key.is_plural() {
+                    for &category in plural::categories(&self.locale) {
+                        let template = self.flatten(entry, value, Some(category), &mut diagnostics);
+                        catalog.insert(&plural_name(key, category), &template);
+                    }
+                } else {
+                    let template = self.flatten(entry, value, None, &mut diagnostics);
+                    catalog.insert(key.name(), &template);
+                }
+            }
+
+            for (name, pattern) in &entry.attributes {
+                if let Some(register) = Register::from_name(name) {
+                    let template = self.flatten(entry, pattern, None, &mut diagnostics);
+                    catalog.insert(&register_name(key.name(), register), &template);
+                }
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(catalog)
+        } else {
+            diagnostics.sort_by_key(|diagnostic| diagnostic.position);
+            diagnostics.dedup();
+            Err(diagnostics)
+        }
+    }
+
+    // The line of the message that `to_catalog` made the entry `name`
+    // from: `greetings.hello@casual` comes from `greetings-hello`, and
+    // `farewells.farewells.one` from `farewells-farewells`.
+    pub fn line_of(&self, name: &str) -> Option<usize> {
+        let name = split_register(name).0;
+        let key = PhraseKey::all().iter().find(|&&key| {
+            key.name() == name
+                || PluralCategory::all()
+                    .iter()
+                    .any(|&category| plural_name(key, category) == name)
+        })?;
+        self.messages
+            .get(&message_id(*key))
+            .map(|entry| entry.position.line)
+    }
+
+    fn flatten(
+        &self,
+        entry: &Entry,
+        pattern: &Pattern,
+        category: Option<PluralCategory>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> String {
+        let mut flattener = Flattener::new(self, category);
+        let template = flattener.pattern(pattern);
+        diagnostics.extend(flattener.diagnostics);
+        if template.contains('\n') {
+            diagnostics.push(Diagnostic::new(
+                entry.position,
+                &format!(
+                    "`{}` spans several lines, which a catalog cannot hold",
+                    entry.id
+                ),
+            ));
+        }
+        template
+    }
+}
+
+fn kind(sigil: &str) -> &'static str {
+    if sigil.is_empty() {
+        "message"
+    } else {
+        "term"
+    }
+}
+
+// References nested deeper than this are treated as a cycle.
+const MAX_DEPTH: usize = 32;
+
+struct Scope<'a> {
+    bundle: &'a Bundle,
+    args: &'a Args,
+    depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Scope<'a> {
+    fn pattern(&mut self, pattern: &Pattern, out: &mut String) {
+        for element in pattern {
+            match *element {
+                Element::Text(ref text) => out.push_str(text),
+                Element::Placeable(ref expression, position) => {
+                    self.expression(expression, position, out)
+                }
+            }
+        }
+    }
+
+    fn reference(
+        &mut self,
+        target: Result<&Pattern, String>,
+        position: Position,
+        out: &mut String,
+    ) {
+        let pattern = match target {
+            Ok(pattern) => pattern,
+            Err(message) => {
+                self.diagnostics.push(Diagnostic::new(position, &message));
+                return;
+            }
+        };
+        if self.depth >= MAX_DEPTH {
+            self.diagnostics
+                .push(Diagnostic::new(position, "cyclic reference"));
+            return;
+        }
+        self.depth += 1;
+        self.pattern(pattern, out);
+        self.depth -= 1;
+    }
+
+    fn expression(&mut self, expression: &Expression, position: Position, out: &mut String) {
+        match *expression {
+            Expression::String(ref value) => out.push_str(value),
+            Expression::Number(value) => out.push_str(&value.to_string()),
+            Expression::Variable(ref name) => match self.args.get(name) {
+                Some(value) => {
+                    let locale = &self.bundle.locale;
+                    let value = value.format_with(conventions::of(locale));
+                    if direction::needs_isolation(direction::of(locale), &value) {
+                        out.push_str(&direction::isolate(&value))
+                    } else {
+                        out.push_str(&value)
+                    }
+                }
+                None => {
+                    self.diagnostics.push(Diagnostic::new(
+                        position,
+                        &format!("unknown variable `${}`", name),
+                    ));
+                    out.push_str(&format!("{{${}}}", name));
+                }
+            },
+            Expression::Message(ref id, ref attribute) => {
+                let bundle = self.bundle;
+                let target = bundle.target(&bundle.messages, id, attribute.as_ref(), "");
+                self.reference(target, position, out);
+            }
+            Expression::Term(ref id, ref attribute) => {
+                let bundle = self.bundle;
+                let target = bundle.target(&bundle.terms, id, attribute.as_ref(), "-");
+                self.reference(target, position, out);
+            }
+            Expression::Select(ref selector, ref variants) => {
+                // Variants are keyed by the bare number, not `1,000`.
+                let bare = match **selector {
+                    Expression::Variable(ref name) => {
+                        self.args.selector(name).map(|value| value.into_owned())
+                    }
+                    _ => None,
+                };
+                let value = bare.unwrap_or_else(|| {
+                    let mut value = String::new();
+                    self.expression(selector, position, &mut value);
+                    value
+                });
+                let variant = select(&self.bundle.locale, &value, variants);
+                self.pattern(&variant.value, out);
+            }
+        }
+    }
+}
+
+// Picks the variant whose key equals `value`, or for a whole number the one
+// named after its plural category, or else the default.
+fn select<'v>(locale: &Locale, value: &str, variants: &'v [Variant]) -> &'v Variant {
+    let category = value
+        .parse::<u64>()
+        .ok()
+        .map(|count| plural::category(locale, count));
+    variants
+        .iter()
+        .find(|variant| variant.key == value)
+        .or_else(|| {
+            let category = category?;
+            variants
+                .iter()
+                .find(|variant| variant.key == category.name())
+        })
+        .or_else(|| variants.iter().find(|variant| variant.default))
+        .unwrap_or(&variants[0])
+}
+
+// Turns a pattern into a catalog template: variables become `{name}`,
+// references and literals are inlined, selects on `$count` are resolved for
+// one plural category and other selects become catalog selects.
+struct Flattener<'a> {
+    bundle: &'a Bundle,
+    category: Option<PluralCategory>,
+    depth: usize,
+    // The select whose variant is being written, if any.
+    select: Option<Position>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Flattener<'a> {
+    fn new(bundle: &'a Bundle, category: Option<PluralCategory>) -> Flattener<'a> {
+        Flattener {
+            bundle,
+            category,
+            depth: 0,
+            select: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    // Escapes literal braces. Inside a catalog select's variant every `}`
+    // closes the variant, so a literal one cannot be written there.
+    fn literal(&mut self, text: &str) -> String {
+        if let Some(position) = self.select {
+            if text.contains('}') {
+                self.diagnostics.push(Diagnostic::new(
+                    position,
+                    "a variant of a catalog select cannot contain a literal `}`",
+                ));
+            }
+        }
+        text.replace('{', "{{").replace('}', "}}")
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> String {
+        let mut out = String::new();
+        for element in pattern {
+            match *element {
+                Element::Text(ref text) => {
+                    let text = self.literal(text);
+                    out.push_str(&text)
+                }
+                Element::Placeable(ref expression, position) => {
+                    let text = self.expression(expression, position);
+                    out.push_str(&text);
+                }
+            }
+        }
+        out
+    }
+
+    fn reference(&mut self, target: Result<&Pattern, String>, position: Position) -> String {
+        match target {
+            Ok(_) if self.depth >= MAX_DEPTH => {
+                self.diagnostics
+                    .push(Diagnostic::new(position, "cyclic reference"));
+                String::new()
+            }
+            Ok(pattern) => {
+                self.depth += 1;
+                let text = self.pattern(pattern);
+                self.depth -= 1;
+                text
+            }
+            Err(message) => {
+                self.diagnostics.push(Diagnostic::new(position, &message));
+                String::new()
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression, position: Position) -> String {
+        let bundle = self.bundle;
+        match *expression {
+            Expression::String(ref value) => self.literal(value),
+            Expression::Number(value) => value.to_string(),
+            Expression::Variable(ref name) => format!("{{{}}}", name),
+            Expression::Message(ref id, ref attribute) => self.reference(
+                bundle.target(&bundle.messages, id, attribute.as_ref(), ""),
+                position,
+            ),
+            Expression::Term(ref id, ref attribute) => self.reference(
+                bundle.target(&bundle.terms, id, attribute.as_ref(), "-"),
+                position,
+            ),
+            Expression::Select(ref selector, ref variants) => {
+                let name = match **selector {
+                    Expression::Variable(ref name) => name,
+                    _ => {
+                        self.diagnostics.push(Diagnostic::new(
+                            position,
+                            "a catalog phrase may only select on a variable",
+                        ));
+                        return String::new();
+                    }
+                };
+                match self.category {
+                    Some(category) if name == "count" => {
+                        let variant = variants
+                            .iter()
+                            .find(|variant| variant.key == category.name())
+                            .or_else(|| variants.iter().find(|variant| variant.default))
+                            .unwrap_or(&variants[0]);
+                        self.pattern(&variant.value)
+                    }
+                    _ => self.select(name, variants, position),
+                }
+            }
+        }
+    }
+
+    // Writes `{name, select, key {...} other {...}}`, with the default
+    // variant as `other`.
+    fn select(&mut self, name: &str, variants: &[Variant], position: Position) -> String {
+        let mut out = format!("{{{}, select,", name);
+        let default = variants
+            .iter()
+            .find(|variant| variant.default)
+            .unwrap_or(&variants[0]);
+        let keyed = variants
+            .iter()
+            .filter(|variant| !variant.default && variant.key != "other")
+            .map(|variant| (variant.key.as_str(), variant))
+            .chain(Some(("other", default)));
+        for (key, variant) in keyed {
+            let outer = self.select.replace(position);
+            let text = self.pattern(&variant.value);
+            self.select = outer;
+            out.push_str(&format!(" {} {{{}}}", key, text));
+        }
+        out.push('}');
+        out
+    }
+}
@@ -0,0 +1,497 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use catalog::{plural_name, Catalog};
+use locale::{Locale, ParseLocaleError};
+use phrasebook::PhraseKey;
+use plural::{self, PluralCategory};
+use register::split_register;
+
+// Catalogs travel through gettext with the phrase key as `msgctxt`, the
+// reference (English) text as `msgid` and the translation as `msgstr`.
+// Plural phrases use `msgid_plural` and one `msgstr[N]` per form of the
+// target language, in the order of `plural::categories`.
+
+const MO_MAGIC: u32 = 0x9504_12de;
+const CONTEXT_SEPARATOR: char = '\u{4}';
+
+// One gettext message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    pub context: Option<String>,
+    pub id: String,
+    pub id_plural: Option<String>,
+    pub translations: Vec<String>,
+    pub fuzzy: bool,
+}
+
+#[derive(Debug)]
+pub enum GettextError {
+    Syntax { line: usize, message: String },
+    Mo(String),
+    MissingLanguage,
+    Locale(ParseLocaleError),
+    MissingContext(String),
+}
+
+impl fmt::Display for GettextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GettextError::Syntax { line, ref message } => write!(f, "line {}: {}", line, message),
+            GettextError::Mo(ref message) => write!(f, "invalid .mo file: {}", message),
+            GettextError::MissingLanguage => f.write_str("header has no `Language` field"),
+            GettextError::Locale(ref err) => err.fmt(f),
+            GettextError::MissingContext(ref id) => {
+                write!(f, "message `{}` has no msgctxt naming its phrase key", id)
+            }
+        }
+    }
+}
+
+impl Error for GettextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GettextError::Locale(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// The messages translating `reference` into `catalog`. Untranslated phrases
+// get empty `msgstr`s, which is how gettext marks them.
+pub fn messages(reference: &Catalog, catalog: &Catalog) -> Vec<Message> {
+    let text = |name: &str| catalog.get(name).unwrap_or("").to_string();
+    let mut messages = Vec::new();
+
+    for &key in PhraseKey::all() {
+        if key.is_plural() {
+            let one = reference.get(&plural_name(key, PluralCategory::One));
+            let other = reference
+                .get(&plural_name(key, PluralCategory::Other))
+                .unwrap_or("");
+            messages.push(Message {
+                context: Some(key.name().to_string()),
+                id: one.unwrap_or(other).to_string(),
+                id_plural: Some(other.to_string()),
+                translations: plural::categories(catalog.locale())
+                    .iter()
+                    .map(|&category| text(&plural_name(key, category)))
+                    .collect(),
+                fuzzy: false,
+            });
+            continue;
+        }
+
+        let base = match reference.get(key.name()) {
+            Some(base) => base,
+            None => continue,
+        };
+        messages.push(Message {
+            context: Some(key.name().to_string()),
+            id: base.to_string(),
+            translations: vec![text(key.name())],
+            ..Message::default()
+        });
+
+        // Register forms either side has, translated from the reference's
+        // own form where it has one.
+        let mut registers: Vec<&str> = reference
+            .keys()
+            .chain(catalog.keys())
+            .filter(|name| split_register(name).0 == key.name() && split_register(name).1.is_some())
+            .collect();
+        registers.sort();
+        registers.dedup();
+        for name in registers {
+            messages.push(Message {
+                context: Some(name.to_string()),
+                id: reference.get(name).unwrap_or(base).to_string(),
+                translations: vec![text(name)],
+                ..Message::default()
+            });
+        }
+    }
+    messages
+}
+
+fn header(catalog: &Catalog) -> String {
+    format!(
+        "Project-Id-Version: phrases\nLanguage: {}\nMIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\n\
+         Content-Transfer-Encoding: 8bit\nPlural-Forms: {}\n",
+        catalog.locale(),
+        plural::gettext_plural_forms(catalog.locale())
+    )
+}
+
+// Builds a catalog from imported messages. Fuzzy and untranslated messages
+// are skipped, as gettext itself does.
+pub fn catalog(locale: Locale, messages: &[Message]) -> Result<Catalog, GettextError> {
+    let mut catalog = Catalog::new(locale);
+    for message in messages {
+        if message.id.is_empty() || message.fuzzy {
+            continue;
+        }
+        let context = match message.context {
+            Some(ref context) => context,
+            None => return Err(GettextError::MissingContext(message.id.clone())),
+        };
+
+        if message.id_plural.is_some() {
+            let categories = plural::categories(catalog.locale());
+            for (&category, text) in categories.iter().zip(&message.translations) {
+                if !text.is_empty() {
+                    catalog.insert(&format!("{}.{}", context, category.name()), text);
+                }
+            }
+        } else if let Some(text) = message.translations.first() {
+            if !text.is_empty() {
+                catalog.insert(context, text);
+            }
+        }
+    }
+    Ok(catalog)
+}
+
+// The locale named by a PO/MO header's `Language` field.
+fn header_locale(header: &str) -> Result<Locale, GettextError> {
+    header
+        .lines()
+        .filter_map(|line| {
+            let at = line.find(':')?;
+            if line[..at].trim() == "Language" {
+                Some(line[at + 1..].trim())
+            } else {
+                None
+            }
+        })
+        .find(|tag| !tag.is_empty())
+        .ok_or(GettextError::MissingLanguage)?
+        .parse()
+        .map_err(GettextError::Locale)
+}
+
+fn header_of(messages: &[Message]) -> &str {
+    messages
+        .iter()
+        .find(|message| message.context.is_none() && message.id.is_empty())
+        .and_then(|message| message.translations.first())
+        .map(|header| header.as_str())
+        .unwrap_or("")
+}
+
+// Writes `catalog` as a `.po` file translating `reference`.
+pub fn to_po(reference: &Catalog, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    out.push_str("msgid \"\"\n");
+    out.push_str(&format!("msgstr {}\n", quote(&header(catalog))));
+
+    for message in messages(reference, catalog) {
+        out.push('\n');
+        if let Some(ref context) = message.context {
+            out.push_str(&format!("msgctxt {}\n", quote(context)));
+        }
+        out.push_str(&format!("msgid {}\n", quote(&message.id)));
+        match message.id_plural {
+            Some(ref id_plural) => {
+                out.push_str(&format!("msgid_plural {}\n", quote(id_plural)));
+                for (index, text) in message.translations.iter().enumerate() {
+                    out.push_str(&format!("msgstr[{}] {}\n", index, quote(text)));
+                }
+            }
+            None => {
+                let text = message.translations.first().map(|text| text.as_str());
+                out.push_str(&format!("msgstr {}\n", quote(text.unwrap_or(""))));
+            }
+        }
+    }
+    out
+}
+
+// Reads a `.po` file. The locale comes from the header's `Language` field.
+pub fn from_po(source: &str) -> Result<Catalog, GettextError> {
+    let messages = parse_po(source)?;
+    let locale = header_locale(header_of(&messages))?;
+    catalog(locale, &messages)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Context,
+    Id,
+    IdPlural,
+    Translation(usize),
+}
+
+// Splits a `.po` file into messages. A message ends at its last `msgstr`;
+// the comments, `msgctxt` or `msgid` after that start the next one.
+pub fn parse_po(source: &str) -> Result<Vec<Message>, GettextError> {
+    let mut messages = Vec::new();
+    let mut message = Message::default();
+    let mut field = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        let syntax = |message: &str| GettextError::Syntax {
+            line: line_number,
+            message: message.to_string(),
+        };
+
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        }
+
+        if line.starts_with('"') {
+            let text = unquote(line).ok_or_else(|| syntax("malformed string"))?;
+            match field {
+                Some(field) => append(&mut message, field, &text),
+                None => return Err(syntax("string outside of a message")),
+            }
+            continue;
+        }
+
+        let next = if line.starts_with('#') {
+            None
+        } else {
+            let at = line
+                .find(' ')
+                .ok_or_else(|| syntax("expected a keyword and a string"))?;
+            let keyword = &line[..at];
+            Some(match keyword {
+                "msgctxt" => Field::Context,
+                "msgid" => Field::Id,
+                "msgid_plural" => Field::IdPlural,
+                "msgstr" => Field::Translation(0),
+                _ if keyword.starts_with("msgstr[") && keyword.ends_with(']') => {
+                    let index: usize = keyword[7..keyword.len() - 1]
+                        .parse()
+                        .map_err(|_| syntax("invalid plural index"))?;
+                    // No language has more forms than there are categories.
+                    if index >= PluralCategory::all().len() {
+                        return Err(syntax(&format!("plural index {} out of range", index)));
+                    }
+                    Field::Translation(index)
+                }
+                _ => return Err(syntax(&format!("unknown keyword `{}`", keyword))),
+            })
+        };
+
+        let ends_message = matches!(field, Some(Field::Translation(_)))
+            && matches!(next, None | Some(Field::Context) | Some(Field::Id));
+        if ends_message {
+            messages.push(message);
+            message = Message::default();
+            field = None;
+        }
+
+        let next = match next {
+            Some(next) => next,
+            None => {
+                if line.starts_with("#,") && line.contains("fuzzy") {
+                    message.fuzzy = true;
+                }
+                continue;
+            }
+        };
+        match next {
+            Field::Context => message.context = Some(String::new()),
+            Field::IdPlural => message.id_plural = Some(String::new()),
+            Field::Translation(index) => {
+                while message.translations.len() <= index {
+                    message.translations.push(String::new());
+                }
+            }
+            Field::Id => {}
+        }
+        let at = line.find(' ').unwrap_or(line.len());
+        let text = unquote(line[at..].trim()).ok_or_else(|| syntax("malformed string"))?;
+        append(&mut message, next, &text);
+        field = Some(next);
+    }
+
+    if field.is_some() {
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+fn append(message: &mut Message, field: Field, text: &str) {
+    let target = match field {
+        Field::Context => message.context.get_or_insert_with(String::new),
+        Field::Id => &mut message.id,
+        Field::IdPlural => message.id_plural.get_or_insert_with(String::new),
+        Field::Translation(index) => &mut message.translations[index],
+    };
+    target.push_str(text);
+}
+
+fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn unquote(text: &str) -> Option<String> {
+    if text.len() < 2 || !text.starts_with('"') || !text.ends_with('"') {
+        return None;
+    }
+    let mut out = String::new();
+    let mut chars = text[1..text.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            c @ '"' | c @ '\\' => out.push(c),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+// The `.mo` lookup string for a message: `context EOT id [NUL id_plural]`.
+fn mo_original(message: &Message) -> String {
+    let mut original = String::new();
+    if let Some(ref context) = message.context {
+        original.push_str(context);
+        original.push(CONTEXT_SEPARATOR);
+    }
+    original.push_str(&message.id);
+    if let Some(ref id_plural) = message.id_plural {
+        original.push('\0');
+        original.push_str(id_plural);
+    }
+    original
+}
+
+// Compiles `catalog` into a little-endian `.mo` file translating `reference`.
+// Untranslated messages are left out, as `msgfmt` does.
+pub fn to_mo(reference: &Catalog, catalog: &Catalog) -> Vec<u8> {
+    let mut entries = vec![(String::new(), header(catalog))];
+    for message in messages(reference, catalog) {
+        if message.translations.iter().all(|text| text.is_empty()) {
+            continue;
+        }
+        entries.push((mo_original(&message), message.translations.join("\0")));
+    }
+    entries.sort();
+
+    let count = entries.len() as u32;
+    let originals_offset = 28;
+    let translations_offset = originals_offset + 8 * count;
+    let mut data_offset = translations_offset + 8 * count;
+
+    let mut tables = Vec::new();
+    let mut data = Vec::new();
+    for strings in &[0, 1] {
+        for entry in &entries {
+            let text = if *strings == 0 { &entry.0 } else { &entry.1 };
+            tables.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            tables.extend_from_slice(&data_offset.to_le_bytes());
+            data.extend_from_slice(text.as_bytes());
+            data.push(0);
+            data_offset += text.len() as u32 + 1;
+        }
+    }
+
+    let mut out = Vec::new();
+    for word in &[
+        MO_MAGIC,
+        0,
+        count,
+        originals_offset,
+        translations_offset,
+        0,
+        data_offset,
+    ] {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out.extend_from_slice(&tables);
+    out.extend_from_slice(&data);
+    out
+}
+
+// Reads a `.mo` file of either byte order.
+pub fn from_mo(bytes: &[u8]) -> Result<Catalog, GettextError> {
+    let invalid = |message: &str| GettextError::Mo(message.to_string());
+    let word = |at: usize, big_endian: bool| -> Result<u32, GettextError> {
+        let raw = at
+            .checked_add(4)
+            .and_then(|end| bytes.get(at..end))
+            .ok_or_else(|| invalid("truncated"))?;
+        let raw = [raw[0], raw[1], raw[2], raw[3]];
+        Ok(if big_endian {
+            u32::from_be_bytes(raw)
+        } else {
+            u32::from_le_bytes(raw)
+        })
+    };
+
+    let big_endian = match word(0, false)? {
+        MO_MAGIC => false,
+        magic if magic.swap_bytes() == MO_MAGIC => true,
+        _ => return Err(invalid("bad magic number")),
+    };
+    let count = word(8, big_endian)? as usize;
+    let originals = word(12, big_endian)? as usize;
+    let translations = word(16, big_endian)? as usize;
+    // Both tables must lie inside the file before `count` is trusted.
+    let fits = |table: usize| {
+        count
+            .checked_mul(8)
+            .and_then(|size| table.checked_add(size))
+            .is_some_and(|end| end <= bytes.len())
+    };
+    if !fits(originals) || !fits(translations) {
+        return Err(invalid("string table out of bounds"));
+    }
+
+    let string = |table: usize, index: usize| -> Result<&str, GettextError> {
+        let len = word(table + 8 * index, big_endian)? as usize;
+        let at = word(table + 8 * index + 4, big_endian)? as usize;
+        let raw = at
+            .checked_add(len)
+            .and_then(|end| bytes.get(at..end))
+            .ok_or_else(|| invalid("string out of bounds"))?;
+        ::core::str::from_utf8(raw).map_err(|_| invalid("string is not UTF-8"))
+    };
+
+    let mut messages = Vec::with_capacity(count);
+    for index in 0..count {
+        let original = string(originals, index)?;
+        let (context, original) = match original.find(CONTEXT_SEPARATOR) {
+            Some(at) => (Some(original[..at].to_string()), &original[at + 1..]),
+            None => (None, original),
+        };
+        let mut ids = original.splitn(2, '\0');
+        messages.push(Message {
+            context,
+            id: ids.next().unwrap_or("").to_string(),
+            id_plural: ids.next().map(|id| id.to_string()),
+            translations: string(translations, index)?
+                .split('\0')
+                .map(|text| text.to_string())
+                .collect(),
+            fuzzy: false,
+        });
+    }
+
+    let locale = header_locale(header_of(&messages))?;
+    catalog(locale, &messages)
+}
@@ -1,3 +1,17 @@
+use alloc::string::String;
+
+use phrasebook::PhraseKey;
+use plural::PluralCategory;
+
 pub fn goodbye() -> String {
-    "さようなら".to_string()
+    super::text(PhraseKey::Goodbye)
+}
+
+pub fn goodbye_to() -> String {
+    super::text(PhraseKey::GoodbyeTo)
+}
+
+// Japanese does not inflect for number, so every category reads the same.
+pub fn farewells(_category: PluralCategory) -> String {
+    super::text(PhraseKey::Farewells)
 }
\ No newline at end of file
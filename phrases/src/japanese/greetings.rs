@@ -1,3 +1,11 @@
+use alloc::string::String;
+
+use phrasebook::PhraseKey;
+
 pub fn hello() -> String {
-    "こんにちは".to_string()
+    super::text(PhraseKey::Hello)
+}
+
+pub fn hello_to() -> String {
+    super::text(PhraseKey::HelloTo)
 }
\ No newline at end of file
@@ -1,2 +1,40 @@
 pub mod greetings;
-pub mod farewells;
\ No newline at end of file
+pub mod farewells;
+pub mod script;
+
+use alloc::string::String;
+
+use builtin;
+use locale::Locale;
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::PluralCategory;
+use register::Register;
+
+pub struct Japanese;
+
+impl Phrasebook for Japanese {
+    fn locale(&self) -> Locale {
+        builtin::JA.locale()
+    }
+
+    fn phrase(&self, key: PhraseKey) -> Option<String> {
+        builtin::JA.phrase(key)
+    }
+
+    fn phrase_in(&self, key: PhraseKey, register: Register) -> Option<String> {
+        builtin::JA.phrase_in(key, register)
+    }
+
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        builtin::JA.plural(key, category)
+    }
+}
+
+fn text(key: PhraseKey) -> String {
+    Japanese.phrase(key).unwrap_or_default()
+}
+
+// A phrase in Hepburn romaji, for readers who do not read kana.
+pub fn romanized(key: PhraseKey) -> String {
+    script::romaji(&text(key))
+}
@@ -0,0 +1,307 @@
+// Conversion between hiragana, katakana and Hepburn romaji. Anything that is
+// not kana (kanji, Latin text, `{placeholders}`) passes through unchanged.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const HIRAGANA_START: u32 = 0x3041;
+const HIRAGANA_END: u32 = 0x3096;
+const KATAKANA_OFFSET: u32 = 0x60;
+
+pub fn to_katakana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ HIRAGANA_START..=HIRAGANA_END => shift(code + KATAKANA_OFFSET, c),
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ 0x30A1..=0x30F6 => shift(code - KATAKANA_OFFSET, c),
+            _ => c,
+        })
+        .collect()
+}
+
+fn shift(code: u32, fallback: char) -> char {
+    ::core::char::from_u32(code).unwrap_or(fallback)
+}
+
+const SYLLABLES: &[(char, &str)] = &[
+    ('あ', "a"),
+    ('い', "i"),
+    ('う', "u"),
+    ('え', "e"),
+    ('お', "o"),
+    ('か', "ka"),
+    ('き', "ki"),
+    ('く', "ku"),
+    ('け', "ke"),
+    ('こ', "ko"),
+    ('が', "ga"),
+    ('ぎ', "gi"),
+    ('ぐ', "gu"),
+    ('げ', "ge"),
+    ('ご', "go"),
+    ('さ', "sa"),
+    ('し', "shi"),
+    ('す', "su"),
+    ('せ', "se"),
+    ('そ', "so"),
+    ('ざ', "za"),
+    ('じ', "ji"),
+    ('ず', "zu"),
+    ('ぜ', "ze"),
+    ('ぞ', "zo"),
+    ('た', "ta"),
+    ('ち', "chi"),
+    ('つ', "tsu"),
+    ('て', "te"),
+    ('と', "to"),
+    ('だ', "da"),
+    ('ぢ', "ji"),
+    ('づ', "zu"),
+    ('で', "de"),
+    ('ど', "do"),
+    ('な', "na"),
+    ('に', "ni"),
+    ('ぬ', "nu"),
+    ('ね', "ne"),
+    ('の', "no"),
+    ('は', "ha"),
+    ('ひ', "hi"),
+    ('ふ', "fu"),
+    ('へ', "he"),
+    ('ほ', "ho"),
+    ('ば', "ba"),
+    ('び', "bi"),
+    ('ぶ', "bu"),
+    ('べ', "be"),
+    ('ぼ', "bo"),
+    ('ぱ', "pa"),
+    ('ぴ', "pi"),
+    ('ぷ', "pu"),
+    ('ぺ', "pe"),
+    ('ぽ', "po"),
+    ('ま', "ma"),
+    ('み', "mi"),
+    ('む', "mu"),
+    ('め', "me"),
+    ('も', "mo"),
+    ('や', "ya"),
+    ('ゆ', "yu"),
+    ('よ', "yo"),
+    ('ら', "ra"),
+    ('り', "ri"),
+    ('る', "ru"),
+    ('れ', "re"),
+    ('ろ', "ro"),
+    ('わ', "wa"),
+    ('ゐ', "i"),
+    ('ゑ', "e"),
+    ('を', "o"),
+    ('ゔ', "vu"),
+    ('ぁ', "a"),
+    ('ぃ', "i"),
+    ('ぅ', "u"),
+    ('ぇ', "e"),
+    ('ぉ', "o"),
+    ('ゃ', "ya"),
+    ('ゅ', "yu"),
+    ('ょ', "yo"),
+    ('ゎ', "wa"),
+    ('ゕ', "ka"),
+    ('ゖ', "ke"),
+];
+
+// Kana followed by a small vowel, mostly from katakana loanwords.
+const EXTENDED: &[(&str, &str)] = &[
+    ("いぇ", "ye"),
+    ("うぃ", "wi"),
+    ("うぇ", "we"),
+    ("うぉ", "wo"),
+    ("ゔぁ", "va"),
+    ("ゔぃ", "vi"),
+    ("ゔぇ", "ve"),
+    ("ゔぉ", "vo"),
+    ("しぇ", "she"),
+    ("じぇ", "je"),
+    ("ちぇ", "che"),
+    ("つぁ", "tsa"),
+    ("つぃ", "tsi"),
+    ("つぇ", "tse"),
+    ("つぉ", "tso"),
+    ("てぃ", "ti"),
+    ("でぃ", "di"),
+    ("とぅ", "tu"),
+    ("どぅ", "du"),
+    ("ふぁ", "fa"),
+    ("ふぃ", "fi"),
+    ("ふぇ", "fe"),
+    ("ふぉ", "fo"),
+];
+
+// Set phrases whose は is the topic particle, read "wa".
+const PARTICLE_WA: &[&str] = &["こんにちは", "こんばんは"];
+
+const PUNCTUATION: &[(char, &str)] = &[
+    ('、', ", "),
+    ('。', ". "),
+    ('！', "! "),
+    ('？', "? "),
+    ('　', " "),
+    ('「', "\""),
+    ('」', "\""),
+    ('・', " "),
+    ('〜', "~"),
+];
+
+fn syllable(c: char) -> Option<&'static str> {
+    SYLLABLES
+        .iter()
+        .find(|entry| entry.0 == c)
+        .map(|entry| entry.1)
+}
+
+// The romaji for kana `c` combined with the small kana after it, if the two
+// form one syllable: きゃ is kya, しゃ sha, ふぁ fa.
+fn digraph(c: char, small: char) -> Option<String> {
+    let pair: String = [c, small].iter().collect();
+    if let Some(entry) = EXTENDED.iter().find(|entry| entry.0 == pair) {
+        return Some(entry.1.to_string());
+    }
+    if !matches!(small, 'ゃ' | 'ゅ' | 'ょ') || c == 'い' {
+        return None;
+    }
+    let base = syllable(c)?;
+    let stem = base.strip_suffix('i')?;
+    let vowel = &syllable(small)?[1..];
+    if matches!(stem, "sh" | "ch" | "j") {
+        Some(format!("{}{}", stem, vowel))
+    } else {
+        Some(format!("{}y{}", stem, vowel))
+    }
+}
+
+fn lengthen(vowel: char) -> Option<char> {
+    match vowel {
+        'a' => Some('ā'),
+        'i' => Some('ī'),
+        'u' => Some('ū'),
+        'e' => Some('ē'),
+        'o' => Some('ō'),
+        _ => None,
+    }
+}
+
+enum Token {
+    Kana(String),
+    SmallTsu,
+    Syllabic,
+    LongMark,
+    Other(String),
+}
+
+fn tokens(text: &str) -> Vec<Token> {
+    let mut text = to_hiragana(text);
+    for phrase in PARTICLE_WA {
+        let spoken = phrase.replace('は', "わ");
+        text = text.replace(phrase, &spoken);
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(romaji) = chars.get(i + 1).and_then(|&small| digraph(c, small)) {
+            tokens.push(Token::Kana(romaji));
+            i += 2;
+            continue;
+        }
+        tokens.push(match c {
+            'っ' => Token::SmallTsu,
+            'ん' => Token::Syllabic,
+            'ー' => Token::LongMark,
+            _ => match syllable(c) {
+                Some(romaji) => Token::Kana(romaji.to_string()),
+                None => match PUNCTUATION.iter().find(|entry| entry.0 == c) {
+                    Some(entry) => Token::Other(entry.1.to_string()),
+                    None => Token::Other(c.to_string()),
+                },
+            },
+        });
+        i += 1;
+    }
+    tokens
+}
+
+// Romanizes kana using modified Hepburn: long vowels take a macron (おう and
+// ー alike), small tsu doubles the next consonant (っち is "tchi"), ん before
+// a vowel or y is written "n'", and the は of greetings such as こんにちは
+// is read "wa". Kanji cannot be read without a dictionary and are left as
+// they are.
+pub fn romaji(text: &str) -> String {
+    let tokens = tokens(text);
+    let mut out = String::new();
+    let mut after_kana = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let next = match tokens.get(i + 1) {
+            Some(Token::Kana(romaji)) => romaji.chars().next(),
+            _ => None,
+        };
+        match *token {
+            Token::Kana(ref romaji) => {
+                let last = out.chars().last();
+                let long = after_kana
+                    && matches!(
+                        (last, romaji.as_str()),
+                        (Some('a'), "a")
+                            | (Some('u'), "u")
+                            | (Some('e'), "e")
+                            | (Some('o'), "o")
+                            | (Some('o'), "u")
+                    );
+                match last.and_then(lengthen) {
+                    Some(vowel) if long => {
+                        out.pop();
+                        out.push(vowel);
+                    }
+                    _ => out.push_str(romaji),
+                }
+                after_kana = true;
+            }
+            Token::SmallTsu => {
+                match next {
+                    Some('c') => out.push('t'),
+                    Some(c) if !"aiueo".contains(c) => out.push(c),
+                    _ => {}
+                }
+                after_kana = false;
+            }
+            Token::Syllabic => {
+                out.push('n');
+                if next.is_some_and(|c| "aiueoy".contains(c)) {
+                    out.push('\'');
+                }
+                after_kana = false;
+            }
+            Token::LongMark => {
+                if let Some(vowel) = out.chars().last().and_then(lengthen) {
+                    out.pop();
+                    out.push(vowel);
+                }
+                after_kana = false;
+            }
+            Token::Other(ref text) => {
+                out.push_str(text);
+                after_kana = false;
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
@@ -0,0 +1,84 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+// Just enough JSON writing for the CLI and statistics dumps, so the crate
+// stays free of dependencies.
+
+// `text` as a quoted, escaped JSON string.
+pub fn string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// A JSON array of already-encoded values.
+pub fn array<I>(values: I) -> String
+where
+    I: IntoIterator<Item = String>,
+{
+    let values: Vec<String> = values.into_iter().collect();
+    format!("[{}]", values.join(","))
+}
+
+// A JSON object built field by field, in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct Object {
+    fields: Vec<(String, String)>,
+}
+
+impl Object {
+    pub fn new() -> Object {
+        Object::default()
+    }
+
+    // Adds a field whose value is already encoded JSON.
+    pub fn raw(mut self, name: &str, json: String) -> Object {
+        self.fields.push((name.to_string(), json));
+        self
+    }
+
+    pub fn string(self, name: &str, value: &str) -> Object {
+        self.raw(name, string(value))
+    }
+
+    pub fn number<T: fmt::Display>(self, name: &str, value: T) -> Object {
+        self.raw(name, value.to_string())
+    }
+
+    pub fn boolean(self, name: &str, value: bool) -> Object {
+        self.raw(name, value.to_string())
+    }
+
+    pub fn optional(self, name: &str, value: Option<&str>) -> Object {
+        match value {
+            Some(value) => self.string(name, value),
+            None => self.raw(name, "null".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{")?;
+        for (index, (name, json)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}:{}", string(name), json)?;
+        }
+        f.write_str("}")
+    }
+}
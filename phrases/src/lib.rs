@@ -1,2 +1,140 @@
+// Phrase lookup and formatting for `no_std` targets with an allocator.
+// Everything that touches files, threads, sockets or the system clock is
+// behind the default `std` feature.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate core;
+
+mod builtin;
+pub mod catalog;
+pub mod conventions;
+#[cfg(feature = "std")]
+pub mod context;
+pub mod coverage;
+pub mod direction;
 pub mod english;
-pub mod japanese;
\ No newline at end of file
+pub mod fallback;
+pub mod fluent;
+pub mod gettext;
+pub mod japanese;
+pub mod json;
+pub mod lint;
+pub mod locale;
+pub mod message;
+pub mod negotiate;
+pub mod observe;
+pub mod phrasebook;
+pub mod plural;
+pub mod pseudo;
+pub mod register;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod server;
+mod syntax;
+pub mod time;
+pub mod width;
+
+pub use catalog::{Catalog, CatalogError, Catalogs};
+pub use conventions::{Currency, DateStyle};
+pub use coverage::Coverage;
+pub use direction::Direction;
+pub use fallback::{Resolved, Resolver};
+pub use locale::{Locale, ParseLocaleError};
+pub use message::{Args, FormatError, Value};
+pub use negotiate::{Negotiated, Preference};
+#[cfg(feature = "std")]
+pub use observe::Counter;
+pub use observe::{Event, Observer, Outcome};
+pub use phrasebook::{PhraseKey, Phrasebook};
+pub use plural::PluralCategory;
+pub use pseudo::Pseudo;
+pub use register::Register;
+#[cfg(feature = "std")]
+pub use registry::{PhraseRegistry, Snapshot};
+#[cfg(feature = "std")]
+pub use time::SystemClock;
+pub use time::{Clock, Date, DayPeriod, FixedClock, Time};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+// The built-in phrasebook for exactly `locale`, if there is one. Every
+// catalog in `catalogs/` is built in, as are the pseudo-locales.
+pub fn phrasebook(locale: &Locale) -> Option<&'static dyn Phrasebook> {
+    builtin::ALL
+        .iter()
+        .find(|book| book.locale() == *locale)
+        .map(|&book| book as &dyn Phrasebook)
+        .or_else(|| {
+            Pseudo::all()
+                .iter()
+                .find(|pseudo| pseudo.locale() == *locale)
+                .map(|pseudo| pseudo as &dyn Phrasebook)
+        })
+}
+
+// Every locale with a built-in phrasebook.
+pub fn locales() -> Vec<Locale> {
+    builtin::ALL
+        .iter()
+        .map(|book| book.locale())
+        .chain(Pseudo::all().iter().map(|pseudo| pseudo.locale()))
+        .collect()
+}
+
+// Looks `key` up in the phrasebook for `locale`. A regional locale such as
+// `ja-JP` is served by its language's phrasebook.
+pub fn lookup(locale: &Locale, key: PhraseKey) -> Option<String> {
+    serving_phrasebook(locale).and_then(|book| book.phrase(key))
+}
+
+// Looks `key` up like `lookup`, preferring the locale's form for
+// `register` where it has one.
+pub fn lookup_with_register(locale: &Locale, key: PhraseKey, register: Register) -> Option<String> {
+    serving_phrasebook(locale).and_then(|book| register::select(book, key, register))
+}
+
+// The greeting for the part of the day `clock` reports, e.g. "Good
+// morning!" or こんばんは.
+pub fn greeting(locale: &Locale, clock: &dyn Clock) -> Option<String> {
+    greeting_at(locale, clock.now())
+}
+
+pub fn greeting_at(locale: &Locale, time: Time) -> Option<String> {
+    lookup(locale, DayPeriod::of(time).greeting())
+}
+
+fn serving_phrasebook(locale: &Locale) -> Option<&'static dyn Phrasebook> {
+    phrasebook(locale).or_else(|| phrasebook(&Locale::new(locale.language())))
+}
+
+// Looks `key` up like `lookup` and fills in its placeholders from `args`.
+pub fn format(locale: &Locale, key: PhraseKey, args: &Args) -> Result<String, FormatError> {
+    match lookup(locale, key) {
+        Some(template) => message::format_localized(&template, args, locale),
+        None => Err(FormatError::MissingPhrase(key.name().to_string())),
+    }
+}
+
+// Formats a count-dependent phrase, choosing the form for `count` by the
+// locale's plural rules. `count` is passed to the template as `{count}`,
+// written as the locale writes numbers.
+pub fn format_count(
+    locale: &Locale,
+    key: PhraseKey,
+    count: u64,
+    args: &Args,
+) -> Result<String, FormatError> {
+    let template = serving_phrasebook(locale)
+        .and_then(|book| plural::select(book, key, plural::category(locale, count)))
+        .ok_or_else(|| FormatError::MissingPhrase(key.name().to_string()))?;
+    message::format_localized(
+        &template,
+        &args.clone().with_value("count", Value::Number(count as f64)),
+        locale,
+    )
+}
@@ -0,0 +1,373 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use catalog::Catalog;
+use coverage;
+use fluent::Bundle;
+use locale::Locale;
+use message;
+use register::split_register;
+use syntax;
+
+// What a diagnostic is about. Each check has a fixed severity: problems
+// that break lookups or formatting are errors, matters of taste warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Check {
+    // Bytes that are not UTF-8.
+    Encoding,
+    // Lines the catalog grammar cannot read.
+    Syntax,
+    // A key defined more than once; the first definition is linted.
+    DuplicateKey,
+    // A malformed template, or placeholders that differ from the
+    // reference's.
+    Placeholders,
+    // A sentence-final mark the reference has and the translation lacks,
+    // or the other way round.
+    Punctuation,
+    // Whitespace at the end of a line.
+    Whitespace,
+    // Text identical to the reference in another language.
+    Untranslated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+// One problem found in a catalog file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub check: Check,
+    // One-based; zero when the problem has no single line.
+    pub line: usize,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl Check {
+    pub fn all() -> &'static [Check] {
+        &[
+            Check::Encoding,
+            Check::Syntax,
+            Check::DuplicateKey,
+            Check::Placeholders,
+            Check::Punctuation,
+            Check::Whitespace,
+            Check::Untranslated,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Check::Encoding => "encoding",
+            Check::Syntax => "syntax",
+            Check::DuplicateKey => "duplicate-key",
+            Check::Placeholders => "placeholders",
+            Check::Punctuation => "punctuation",
+            Check::Whitespace => "whitespace",
+            Check::Untranslated => "untranslated",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match *self {
+            Check::Punctuation | Check::Whitespace | Check::Untranslated => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl Diagnostic {
+    fn new(check: Check, line: usize, key: Option<&str>, message: String) -> Diagnostic {
+        Diagnostic {
+            check,
+            line,
+            key: key.map(|key| key.to_string()),
+            message,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.check.severity()
+    }
+}
+
+// `12: warning: ... [punctuation]`, so that a file name in front makes the
+// usual `file:line:` form.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}: ", self.line)?;
+        }
+        write!(
+            f,
+            "{}: {} [{}]",
+            self.severity().name(),
+            self.message,
+            self.check.name()
+        )
+    }
+}
+
+// Lints the source of a `.catalog` file for `locale`. Placeholders,
+// punctuation and untranslated text are checked against `reference`,
+// unless it is the catalog's own locale. Diagnostics come in line order.
+pub fn lint(locale: &Locale, source: &[u8], reference: Option<&Catalog>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let text = decode(source, &mut diagnostics);
+    whitespace(&text, &mut diagnostics);
+
+    let (entries, errors) = syntax::parse_all(&text);
+    for error in errors {
+        diagnostics.push(Diagnostic::new(
+            Check::Syntax,
+            error.line,
+            None,
+            error.message,
+        ));
+    }
+
+    let mut catalog = Catalog::new(locale.clone());
+    let mut lines = BTreeMap::new();
+    for entry in entries {
+        if let Some(&first) = lines.get(&entry.key) {
+            let message = format!("`{}` is already defined on line {}", entry.key, first);
+            diagnostics.push(Diagnostic::new(
+                Check::DuplicateKey,
+                entry.line,
+                Some(&entry.key),
+                message,
+            ));
+            continue;
+        }
+        catalog.insert(&entry.key, &entry.value);
+        lines.insert(entry.key, entry.line);
+    }
+
+    entries_against(&catalog, reference, &mut diagnostics, |key| {
+        lines.get(key).cloned().unwrap_or(0)
+    });
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    diagnostics
+}
+
+// Lints the source of a Fluent file like `lint`. Its messages are checked
+// as the catalog entries they become, at the line of the message.
+pub fn lint_fluent(locale: &Locale, source: &[u8], reference: Option<&Catalog>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let text = decode(source, &mut diagnostics);
+    whitespace(&text, &mut diagnostics);
+
+    let parsed = Bundle::parse(locale.clone(), &text)
+        .and_then(|bundle| bundle.to_catalog().map(|catalog| (bundle, catalog)));
+    match parsed {
+        Ok((bundle, catalog)) => entries_against(&catalog, reference, &mut diagnostics, |key| {
+            bundle.line_of(key).unwrap_or(0)
+        }),
+        Err(errors) => {
+            for error in errors {
+                diagnostics.push(Diagnostic::new(
+                    Check::Syntax,
+                    error.position.line,
+                    None,
+                    error.message,
+                ));
+            }
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    diagnostics
+}
+
+// Decodes `source`, reporting each line with bytes that are not UTF-8 and
+// replacing them with U+FFFD.
+fn decode(source: &[u8], diagnostics: &mut Vec<Diagnostic>) -> String {
+    let mut text = String::with_capacity(source.len());
+    let mut line = 1;
+    let mut reported = 0;
+    for chunk in source.utf8_chunks() {
+        text.push_str(chunk.valid());
+        line += chunk.valid().matches('\n').count();
+        if chunk.invalid().is_empty() {
+            continue;
+        }
+        if reported != line {
+            let bytes: Vec<String> = chunk
+                .invalid()
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            diagnostics.push(Diagnostic::new(
+                Check::Encoding,
+                line,
+                None,
+                format!("invalid UTF-8 (bytes {})", bytes.join(" ")),
+            ));
+            reported = line;
+        }
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    text
+}
+
+fn whitespace(text: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for (index, line) in text.lines().enumerate() {
+        if line.len() != line.trim_end().len() {
+            diagnostics.push(Diagnostic::new(
+                Check::Whitespace,
+                index + 1,
+                None,
+                "trailing whitespace".to_string(),
+            ));
+        }
+    }
+}
+
+// Checks every entry's template, then compares the entries with
+// `reference`. `line_of` gives the line an entry was read from.
+fn entries_against<F>(
+    catalog: &Catalog,
+    reference: Option<&Catalog>,
+    diagnostics: &mut Vec<Diagnostic>,
+    line_of: F,
+) where
+    F: Fn(&str) -> usize,
+{
+    let mut malformed = BTreeSet::new();
+    for (name, text) in catalog.entries() {
+        if let Err(err) = message::placeholders(text) {
+            diagnostics.push(Diagnostic::new(
+                Check::Placeholders,
+                line_of(name),
+                Some(name),
+                format!("`{}`: {}", name, err),
+            ));
+            malformed.insert(name);
+        }
+    }
+
+    let reference = match reference {
+        Some(reference) if reference.locale() != catalog.locale() => reference,
+        _ => return,
+    };
+
+    for mismatch in coverage::compare(reference, catalog).mismatched {
+        if malformed.contains(mismatch.key.as_str()) {
+            continue;
+        }
+        let message = format!(
+            "`{}` uses {} where {} uses {}",
+            mismatch.key,
+            braced(&mismatch.found),
+            reference.locale(),
+            braced(&mismatch.expected)
+        );
+        diagnostics.push(Diagnostic::new(
+            Check::Placeholders,
+            line_of(&mismatch.key),
+            Some(&mismatch.key),
+            message,
+        ));
+    }
+
+    let translated = reference.locale().language() != catalog.locale().language();
+    for (name, text) in catalog.entries() {
+        let original = match reference
+            .get(name)
+            .or_else(|| coverage::reference_text(reference, split_register(name).0))
+        {
+            Some(original) => original,
+            None => continue,
+        };
+
+        let (expected, found) = (ending(original), ending(text));
+        if expected != found {
+            let message = match (expected, found) {
+                (Some(expected), Some(found)) => format!(
+                    "`{}` ends with `{}` where {} ends with `{}`",
+                    name,
+                    found,
+                    reference.locale(),
+                    expected
+                ),
+                (Some(expected), None) => format!(
+                    "`{}` does not end with `{}` as {} does",
+                    name,
+                    expected,
+                    reference.locale()
+                ),
+                _ => format!(
+                    "`{}` ends with `{}` but {} does not",
+                    name,
+                    found.unwrap_or_default(),
+                    reference.locale()
+                ),
+            };
+            diagnostics.push(Diagnostic::new(
+                Check::Punctuation,
+                line_of(name),
+                Some(name),
+                message,
+            ));
+        }
+
+        if translated && text == original && has_words(text) {
+            diagnostics.push(Diagnostic::new(
+                Check::Untranslated,
+                line_of(name),
+                Some(name),
+                format!("`{}` is the same as in {}", name, reference.locale()),
+            ));
+        }
+    }
+}
+
+fn braced(names: &[String]) -> String {
+    if names.is_empty() {
+        return "no placeholders".to_string();
+    }
+    let names: Vec<String> = names.iter().map(|name| format!("{{{}}}", name)).collect();
+    names.join(", ")
+}
+
+// The sentence-final mark `text` ends with, ignoring the braces that close
+// placeholders and selects. Full-width marks count as their ASCII forms,
+// so `。` matches `.`.
+fn ending(text: &str) -> Option<char> {
+    let last = text
+        .trim_end_matches(|c: char| c == '}' || c.is_whitespace())
+        .chars()
+        .next_back()?;
+    match last {
+        '.' | '。' | '．' => Some('.'),
+        '!' | '！' => Some('!'),
+        '?' | '？' => Some('?'),
+        ':' | '：' => Some(':'),
+        '…' => Some('…'),
+        _ => None,
+    }
+}
+
+// Whether `text` has letters outside its placeholders, so that finding it
+// unchanged means something was left untranslated.
+fn has_words(text: &str) -> bool {
+    let mut rest = text.to_string();
+    for name in message::placeholders(text).unwrap_or_default() {
+        rest = rest.replace(&format!("{{{}}}", name), "");
+    }
+    rest.chars().any(char::is_alphabetic)
+}
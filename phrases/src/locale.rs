@@ -0,0 +1,89 @@
+use alloc::string::{String, ToString};
+use core::error::Error;
+use core::fmt;
+use core::str::FromStr;
+
+// A language tag such as `en`, `ja` or `ja-JP`. The language subtag is
+// stored lowercase and the region subtag uppercase.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Locale {
+    language: String,
+    region: Option<String>,
+}
+
+impl Locale {
+    pub fn new(language: &str) -> Locale {
+        Locale {
+            language: language.to_lowercase(),
+            region: None,
+        }
+    }
+
+    pub fn with_region(language: &str, region: &str) -> Locale {
+        Locale {
+            language: language.to_lowercase(),
+            region: Some(region.to_uppercase()),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.region {
+            Some(ref region) => write!(f, "{}-{}", self.language, region),
+            None => write!(f, "{}", self.language),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLocaleError {
+    tag: String,
+}
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid locale tag `{}`", self.tag)
+    }
+}
+
+impl Error for ParseLocaleError {}
+
+// Accepts both `ja-JP` and the POSIX-style `ja_JP`.
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(tag: &str) -> Result<Locale, ParseLocaleError> {
+        let error = || ParseLocaleError {
+            tag: tag.to_string(),
+        };
+        let mut subtags = tag.split(['-', '_']);
+
+        let language = subtags.next().unwrap_or("");
+        if language.len() < 2
+            || language.len() > 3
+            || !language.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(error());
+        }
+
+        match (subtags.next(), subtags.next()) {
+            (None, _) => Ok(Locale::new(language)),
+            (Some(region), None) if is_region(region) => Ok(Locale::with_region(language, region)),
+            _ => Err(error()),
+        }
+    }
+}
+
+fn is_region(subtag: &str) -> bool {
+    (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+}
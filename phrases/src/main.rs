@@ -1,14 +1,706 @@
 extern crate phrases;
 
-use phrases::english::greetings;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use phrases::japanese::script;
+use phrases::json::{self, Object};
+use phrases::negotiate::{self, Negotiated, Preference};
+use phrases::server::Server;
+use phrases::{
+    catalog, coverage, direction, fluent, gettext, lint, message, Args, Catalog, Catalogs, Counter,
+    Locale, PhraseKey, PhraseRegistry, Register, Resolved, Resolver,
+};
+
+const USAGE: &str = "usage: phrases <command> [options]
+       phrases --help
+
+commands:
+    list [--locale TAG]                  list locales, or every phrase of one locale
+    get KEY [--locale TAG]               look up a phrase (default: from LC_ALL/LANG)
+    translate KEY [--from TAG] --to TAG  show a phrase in two locales
+    coverage [--reference TAG]           diff every locale against the reference
+    lint FILE|DIR... [--reference TAG]   check catalog files for broken placeholders,
+                                         punctuation, duplicate keys, invalid UTF-8,
+                                         stray whitespace and untranslated text
+    export --locale TAG [--output FILE]  write a gettext .po (or .mo) translation file
+    import FILE [--output FILE]          convert a .po or .mo file into a catalog
+    server [--listen ADDR]               serve phrases as JSON over HTTP (default: 127.0.0.1:8080)
+
+options:
+    --accept-language VALUE              negotiate --locale from an Accept-Language value
+    --format text|json                   output format (default: text)
+    --catalogs DIR                       load DIR/*.catalog and DIR/*.ftl over the built-ins
+    --register casual|polite|formal      prefer phrases in this register
+    --arg NAME[:TYPE]=VALUE              fill in a placeholder (repeatable); TYPE is number,
+                                         percent, currency (`9.5 EUR`), date or long-date
+                                         (`2026-01-31`), written as the locale writes them
+    --attribute NAME=VALUE               choose variants by NAME, e.g. gender=female (repeatable)
+    --count N                            choose the plural form for N
+    --script romaji|hiragana|katakana    transliterate Japanese kana in the output
+    --output FILE                        write to FILE instead of standard output
+
+server endpoints:
+    GET /locales                         every locale with its text direction
+    GET /phrases/{locale}/{key}          a phrase; query parameters as for `get`
+                                         (`register`, `count`, placeholders,
+                                         attributes as `gender:attribute=female`)
+    GET /stats                           hits, fallbacks and misses per locale and key
+The server reloads a --catalogs directory whenever its files change.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Romaji,
+    Hiragana,
+    Katakana,
+}
+
+struct Options {
+    command: String,
+    operands: Vec<String>,
+    locale: Option<Locale>,
+    negotiated: Option<Negotiated>,
+    from: Option<Locale>,
+    to: Option<Locale>,
+    reference: Option<Locale>,
+    format: Format,
+    catalogs: Catalogs,
+    catalogs_dir: Option<String>,
+    register: Option<Register>,
+    args: Args,
+    count: Option<u64>,
+    script: Option<Script>,
+    output: Option<String>,
+    listen: String,
+}
+
+// Why the command line cannot be acted on: a mistake in it, which the
+// usage is shown for, or a --catalogs directory that does not load.
+enum OptionsError {
+    Usage(String),
+    Load(String),
+}
+
+impl From<String> for OptionsError {
+    fn from(message: String) -> OptionsError {
+        OptionsError::Usage(message)
+    }
+}
 
 fn main() {
-    println!("Hello in English: {}", phrases::english::greetings::hello());
-    println!("Hi in English: {}", phrases::english::greetings::hi());
-    println!("Goodbye in English: {}", phrases::english::farewells::goodbye());
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        process::exit(0);
+    }
+    let options = match parse_options(&args) {
+        Ok(options) => options,
+        Err(OptionsError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+        Err(OptionsError::Load(message)) => process::exit(fail(&message)),
+    };
+
+    let code = match options.command.as_str() {
+        "list" => list(&options),
+        "get" => get(&options),
+        "translate" => translate(&options),
+        "coverage" => coverage(&options),
+        "lint" => lint(&options),
+        "export" => export(&options),
+        "import" => import(&options),
+        "server" => server(&options),
+        command => {
+            eprintln!("unknown command `{}`\n\n{}", command, USAGE);
+            2
+        }
+    };
+    process::exit(code);
+}
+
+fn parse_options(args: &[String]) -> Result<Options, OptionsError> {
+    let mut options = Options {
+        command: String::new(),
+        operands: Vec::new(),
+        locale: None,
+        negotiated: None,
+        from: None,
+        to: None,
+        reference: None,
+        format: Format::Text,
+        catalogs: Catalogs::builtin(),
+        catalogs_dir: None,
+        register: None,
+        args: Args::new(),
+        count: None,
+        script: None,
+        output: None,
+        listen: "127.0.0.1:8080".to_string(),
+    };
+
+    let mut accept_language = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if options.command.is_empty() {
+                options.command = arg.clone();
+            } else {
+                options.operands.push(arg.clone());
+            }
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?;
+        match arg.as_str() {
+            "--locale" => options.locale = Some(parse_locale(value)?),
+            "--accept-language" => accept_language = Some(value.clone()),
+            "--from" => options.from = Some(parse_locale(value)?),
+            "--to" => options.to = Some(parse_locale(value)?),
+            "--reference" => options.reference = Some(parse_locale(value)?),
+            "--format" => {
+                options.format = match value.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(format!("unknown format `{}`", value).into()),
+                }
+            }
+            "--catalogs" => {
+                options
+                    .catalogs
+                    .load_dir(value)
+                    .map_err(|err| OptionsError::Load(format!("{}: {}", value, err)))?;
+                options.catalogs_dir = Some(value.clone());
+            }
+            "--listen" => options.listen = value.clone(),
+            "--register" => options.register = Some(value.parse()?),
+            "--arg" => match value.find('=') {
+                Some(at) => options.args.set_parsed(&value[..at], &value[at + 1..])?,
+                None => return Err(format!("expected NAME=VALUE, found `{}`", value).into()),
+            },
+            "--attribute" => match value.find('=') {
+                Some(at) => options.args.set_attribute(&value[..at], &value[at + 1..]),
+                None => return Err(format!("expected NAME=VALUE, found `{}`", value).into()),
+            },
+            "--count" => {
+                options.count = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid count `{}`", value))?,
+                )
+            }
+            "--script" => {
+                options.script = Some(match value.as_str() {
+                    "romaji" => Script::Romaji,
+                    "hiragana" => Script::Hiragana,
+                    "katakana" => Script::Katakana,
+                    _ => return Err(format!("unknown script `{}`", value).into()),
+                })
+            }
+            "--output" => options.output = Some(value.clone()),
+            _ => return Err(format!("unknown option `{}`", arg).into()),
+        }
+    }
+
+    if options.command.is_empty() {
+        return Err("missing command".to_string().into());
+    }
+    // Count-dependent phrases have no registers.
+    if options.register.is_some() && options.count.is_some() {
+        return Err("--register cannot be combined with --count"
+            .to_string()
+            .into());
+    }
+    if let Some(header) = accept_language {
+        let resolver = Resolver::new().with_catalogs(options.catalogs.clone());
+        let negotiated = resolver
+            .negotiate(&header)
+            .ok_or_else(|| format!("no catalog matches Accept-Language `{}`", header))?;
+        if options.locale.is_none() {
+            options.locale = Some(negotiated.locale.clone());
+        }
+        options.negotiated = Some(negotiated);
+    }
+    Ok(options)
+}
+
+fn parse_locale(tag: &str) -> Result<Locale, String> {
+    tag.parse()
+        .map_err(|err: phrases::ParseLocaleError| err.to_string())
+}
+
+fn parse_key(name: &str) -> Result<PhraseKey, String> {
+    PhraseKey::find(name).ok_or_else(|| format!("unknown phrase `{}`", name))
+}
+
+// The locale the environment asks for, negotiated against the loaded
+// catalogs, or English.
+fn default_locale(options: &Options) -> Locale {
+    let english = Locale::new("en");
+    negotiate::from_env()
+        .map(|locale| Preference {
+            locale: Some(locale),
+            quality: 1.0,
+        })
+        .and_then(|preference| {
+            negotiate::negotiate(&[preference], options.catalogs.locales(), &english)
+        })
+        .map_or(english, |negotiated| negotiated.locale)
+}
+
+fn operand_key(options: &Options) -> Result<PhraseKey, String> {
+    match options.operands.first() {
+        Some(name) => parse_key(name),
+        None => Err(format!("`{}` needs a phrase key", options.command)),
+    }
+}
+
+fn fail(message: &str) -> i32 {
+    eprintln!("{}", message);
+    1
+}
+
+// Looks `key` up for `locale`, honouring `--register`, `--arg`,
+// `--attribute`, `--count` and `--script`.
+fn phrase(options: &Options, locale: &Locale, key: PhraseKey) -> Result<Resolved, String> {
+    let resolver = Resolver::new().with_catalogs(options.catalogs.clone());
+    let mut resolved = match options.count {
+        Some(count) => resolver
+            .format_count(locale, key, count, &options.args)
+            .map_err(|err| err.to_string())?,
+        None => {
+            let mut resolved = resolver
+                .resolve_with_register(locale, key, options.register.unwrap_or_default())
+                .ok_or_else(|| format!("no phrase for `{}` in {}", key.name(), locale))?;
+            resolved.text =
+                message::format_localized(&resolved.text, &options.args, &resolved.locale)
+                    .map_err(|err| err.to_string())?;
+            resolved
+        }
+    };
+
+    resolved.text = match options.script {
+        Some(Script::Romaji) => script::romaji(&resolved.text),
+        Some(Script::Hiragana) => script::to_hiragana(&resolved.text),
+        Some(Script::Katakana) => script::to_katakana(&resolved.text),
+        None => resolved.text,
+    };
+    Ok(resolved)
+}
+
+fn resolved_json(resolved: &Resolved) -> Object {
+    Object::new()
+        .string("locale", &resolved.requested.to_string())
+        .string("served_by", &resolved.locale.to_string())
+        .string("direction", direction::of(&resolved.locale).name())
+        .string("text", &resolved.text)
+}
+
+fn list(options: &Options) -> i32 {
+    let locale = match options.locale {
+        Some(ref locale) => locale,
+        None => {
+            let locales: Vec<String> = options
+                .catalogs
+                .locales()
+                .map(|locale| locale.to_string())
+                .collect();
+            match options.format {
+                Format::Text => {
+                    for locale in &locales {
+                        println!("{}", locale);
+                    }
+                }
+                Format::Json => println!(
+                    "{}",
+                    json::array(locales.iter().map(|locale| json::string(locale)))
+                ),
+            }
+            return 0;
+        }
+    };
+
+    let catalog = match options.catalogs.get(locale) {
+        Some(catalog) => catalog,
+        None => return fail(&format!("no catalog for {}", locale)),
+    };
+    match options.format {
+        Format::Text => {
+            for (key, text) in catalog.entries() {
+                println!("{} = {}", key, text);
+            }
+        }
+        Format::Json => {
+            let phrases = catalog
+                .entries()
+                .fold(Object::new(), |phrases, (key, text)| {
+                    phrases.string(key, text)
+                });
+            let output = Object::new()
+                .string("locale", &locale.to_string())
+                .string("direction", direction::of(locale).name())
+                .raw("phrases", phrases.to_string());
+            println!("{}", output);
+        }
+    }
+    0
+}
+
+fn get(options: &Options) -> i32 {
+    let key = match operand_key(options) {
+        Ok(key) => key,
+        Err(message) => return fail(&message),
+    };
+    let locale = options
+        .locale
+        .clone()
+        .unwrap_or_else(|| default_locale(options));
+    let resolved = match phrase(options, &locale, key) {
+        Ok(resolved) => resolved,
+        Err(message) => return fail(&message),
+    };
+
+    match options.format {
+        Format::Text => {
+            if resolved.is_fallback() {
+                eprintln!(
+                    "warning: `{}` has no {} translation; showing {}",
+                    key.name(),
+                    resolved.requested,
+                    resolved.locale
+                );
+            }
+            println!("{}", resolved.text);
+        }
+        Format::Json => {
+            let output = resolved_json(&resolved).string("key", key.name());
+            match options.negotiated {
+                Some(ref negotiated) => {
+                    println!("{}", output.number("quality", negotiated.quality))
+                }
+                None => println!("{}", output),
+            }
+        }
+    }
+    0
+}
+
+fn translate(options: &Options) -> i32 {
+    let key = match operand_key(options) {
+        Ok(key) => key,
+        Err(message) => return fail(&message),
+    };
+    let from = options
+        .from
+        .clone()
+        .unwrap_or_else(|| default_locale(options));
+    let to = match options.to {
+        Some(ref to) => to,
+        None => return fail("`translate` needs --to"),
+    };
+    let (source, target) = match (phrase(options, &from, key), phrase(options, to, key)) {
+        (Ok(source), Ok(target)) => (source, target),
+        (Err(message), _) | (_, Err(message)) => return fail(&message),
+    };
+
+    match options.format {
+        Format::Text => {
+            println!("{}: {}", source.requested, source.text);
+            println!("{}: {}", target.requested, target.text);
+        }
+        Format::Json => {
+            let output = Object::new()
+                .string("key", key.name())
+                .raw("from", resolved_json(&source).to_string())
+                .raw("to", resolved_json(&target).to_string());
+            println!("{}", output);
+        }
+    }
+    0
+}
+
+// Exits non-zero unless every locale translates the reference completely.
+fn coverage(options: &Options) -> i32 {
+    let reference = options
+        .reference
+        .clone()
+        .unwrap_or_else(|| Locale::new("en"));
+    let report = match coverage::report(&options.catalogs, &reference) {
+        Some(report) => report,
+        None => return fail(&format!("no catalog for reference locale {}", reference)),
+    };
+
+    match options.format {
+        Format::Text => {
+            for coverage in &report {
+                print!("{}", coverage);
+            }
+        }
+        Format::Json => {
+            let strings = |keys: &[String]| json::array(keys.iter().map(|key| json::string(key)));
+            let locales = report.iter().map(|coverage| {
+                let mismatched = coverage.mismatched.iter().map(|mismatch| {
+                    Object::new()
+                        .string("key", &mismatch.key)
+                        .raw("expected", strings(&mismatch.expected))
+                        .raw("found", strings(&mismatch.found))
+                        .to_string()
+                });
+                Object::new()
+                    .string("locale", &coverage.locale.to_string())
+                    .string("reference", &coverage.reference.to_string())
+                    .raw("missing", strings(&coverage.missing))
+                    .raw("extra", strings(&coverage.extra))
+                    .raw("mismatched", json::array(mismatched))
+                    .to_string()
+            });
+            println!("{}", json::array(locales));
+        }
+    }
+
+    if report.iter().all(|coverage| coverage.is_complete()) {
+        0
+    } else {
+        1
+    }
+}
+
+// The catalog files named on the command line, directories standing for
+// the `.catalog` and `.ftl` files in them.
+fn lint_paths(operands: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    for operand in operands {
+        let path = Path::new(operand);
+        if !path.is_dir() {
+            paths.push(path.to_path_buf());
+            continue;
+        }
+        let entries = fs::read_dir(path).map_err(|err| format!("{}: {}", operand, err))?;
+        let mut found = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|err| format!("{}: {}", operand, err))?.path();
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            if extension == Some(catalog::EXTENSION) || extension == Some(fluent::EXTENSION) {
+                found.push(path);
+            }
+        }
+        found.sort();
+        paths.extend(found);
+    }
+    Ok(paths)
+}
+
+// Lints catalog files against the reference locale, taken from among the
+// files when one of them loads cleanly and from the loaded catalogs
+// otherwise. Exits non-zero if any file has errors; warnings alone pass.
+fn lint(options: &Options) -> i32 {
+    if options.operands.is_empty() {
+        return fail("`lint` needs catalog files or directories");
+    }
+    let paths = match lint_paths(&options.operands) {
+        Ok(paths) => paths,
+        Err(err) => return fail(&err),
+    };
+    let reference = options
+        .reference
+        .clone()
+        .unwrap_or_else(|| Locale::new("en"));
+
+    let mut files = Vec::new();
+    for path in &paths {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        let locale: Locale = match stem.parse() {
+            Ok(locale) => locale,
+            Err(err) => {
+                return fail(&format!(
+                    "{}: file name is not a locale: {}",
+                    path.display(),
+                    err
+                ))
+            }
+        };
+        match fs::read(path) {
+            Ok(bytes) => files.push((path, locale, bytes)),
+            Err(err) => return fail(&format!("{}: {}", path.display(), err)),
+        }
+    }
+    let reference_catalog = files
+        .iter()
+        .filter(|file| file.1 == reference)
+        .find_map(|file| Catalog::load(file.0).ok())
+        .or_else(|| options.catalogs.get(&reference).cloned());
+
+    let mut errors = 0;
+    let mut reports = Vec::new();
+    for (path, locale, bytes) in &files {
+        let is_fluent = path.extension().and_then(|ext| ext.to_str()) == Some(fluent::EXTENSION);
+        let diagnostics = if is_fluent {
+            lint::lint_fluent(locale, bytes, reference_catalog.as_ref())
+        } else {
+            lint::lint(locale, bytes, reference_catalog.as_ref())
+        };
+        errors += diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity() == lint::Severity::Error)
+            .count();
+        reports.push((path, locale, diagnostics));
+    }
+
+    match options.format {
+        Format::Text => {
+            for (path, _, diagnostics) in &reports {
+                for diagnostic in diagnostics {
+                    println!("{}:{}", path.display(), diagnostic);
+                }
+            }
+        }
+        Format::Json => {
+            let diagnostics = reports.iter().flat_map(|(path, locale, diagnostics)| {
+                diagnostics.iter().map(move |diagnostic| {
+                    Object::new()
+                        .string("file", &path.display().to_string())
+                        .string("locale", &locale.to_string())
+                        .number("line", diagnostic.line)
+                        .optional("key", diagnostic.key.as_deref())
+                        .string("check", diagnostic.check.name())
+                        .string("severity", diagnostic.severity().name())
+                        .string("message", &diagnostic.message)
+                        .to_string()
+                })
+            });
+            println!("{}", json::array(diagnostics));
+        }
+    }
+
+    if errors == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+fn is_mo(path: &str) -> bool {
+    Path::new(path).extension().and_then(|ext| ext.to_str()) == Some("mo")
+}
+
+fn write_output(options: &Options, contents: &[u8]) -> i32 {
+    match options.output {
+        Some(ref path) => match fs::write(path, contents) {
+            Ok(()) => 0,
+            Err(err) => fail(&format!("{}: {}", path, err)),
+        },
+        None => {
+            print!("{}", String::from_utf8_lossy(contents));
+            0
+        }
+    }
+}
+
+// Writes a translation file for `--locale` against the reference catalog;
+// an `--output` ending in `.mo` gets a compiled file.
+fn export(options: &Options) -> i32 {
+    let reference = options
+        .reference
+        .clone()
+        .unwrap_or_else(|| Locale::new("en"));
+    let locale = match options.locale {
+        Some(ref locale) => locale,
+        None => return fail("`export` needs --locale"),
+    };
+    let (reference, catalog) = match (
+        options.catalogs.get(&reference),
+        options.catalogs.get(locale),
+    ) {
+        (Some(reference), Some(catalog)) => (reference, catalog),
+        (None, _) => return fail(&format!("no catalog for reference locale {}", reference)),
+        (_, None) => return fail(&format!("no catalog for {}", locale)),
+    };
+
+    if options.output.as_ref().is_some_and(|path| is_mo(path)) {
+        write_output(options, &gettext::to_mo(reference, catalog))
+    } else {
+        write_output(options, gettext::to_po(reference, catalog).as_bytes())
+    }
+}
+
+fn import(options: &Options) -> i32 {
+    let path = match options.operands.first() {
+        Some(path) => path,
+        None => return fail("`import` needs a .po or .mo file"),
+    };
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => return fail(&format!("{}: {}", path, err)),
+    };
+
+    let catalog = if is_mo(path) {
+        gettext::from_mo(&bytes)
+    } else {
+        match String::from_utf8(bytes) {
+            Ok(source) => gettext::from_po(&source),
+            Err(_) => return fail(&format!("{}: not UTF-8", path)),
+        }
+    };
+    match catalog {
+        Ok(catalog) => write_output(options, catalog.to_string().as_bytes()),
+        Err(err) => fail(&format!("{}: {}", path, err)),
+    }
+}
+
+// Serves phrases until killed. With --catalogs, a watcher thread swaps in
+// the directory's catalogs whenever a file in it changes.
+fn server(options: &Options) -> i32 {
+    let registry = match options.catalogs_dir {
+        Some(ref dir) => match PhraseRegistry::load_dir(dir) {
+            Ok(registry) => registry,
+            Err(err) => return fail(&format!("{}: {}", dir, err)),
+        },
+        None => PhraseRegistry::new(&options.catalogs),
+    };
+    let counter = Arc::new(Counter::new());
+    let registry = Arc::new(registry.with_observer(counter.clone()));
+    let listener = match TcpListener::bind(&options.listen) {
+        Ok(listener) => listener,
+        Err(err) => return fail(&format!("{}: {}", options.listen, err)),
+    };
 
-    println!("Hello in Japanese: {}", phrases::japanese::greetings::hello());
-    println!("Goodbye in Japanese: {}", phrases::japanese::farewells::goodbye());
+    if let Some(ref dir) = options.catalogs_dir {
+        let registry = registry.clone();
+        let dir = dir.clone();
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_secs(1));
+            match registry.reload_if_changed() {
+                Ok(true) => eprintln!("reloaded catalogs from {}", dir),
+                Ok(false) => {}
+                Err(err) => eprintln!("{}: {} (keeping the previous catalogs)", dir, err),
+            }
+        });
+    }
 
-    println!("DDD: {}", greetings::hello());
-}
\ No newline at end of file
+    if let Ok(address) = listener.local_addr() {
+        println!("listening on http://{}", address);
+        let _ = io::stdout().flush();
+    }
+    match Server::new(registry).with_stats(counter).serve(listener) {
+        Ok(()) => 0,
+        Err(err) => fail(&format!("server stopped: {}", err)),
+    }
+}
@@ -0,0 +1,548 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use conventions::{self, Conventions, Currency, DateStyle};
+use direction::{self, Direction};
+use locale::Locale;
+use time::Date;
+
+// Named arguments for a phrase template such as `Hello, {name}!`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    values: BTreeMap<String, Value>,
+    attributes: BTreeMap<String, String>,
+}
+
+// An argument value. Everything but text is written the way the locale
+// the phrase is formatted for writes it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(f64),
+    // A fraction: 0.25 is 25%.
+    Percent(f64),
+    Currency(f64, Currency),
+    Date(Date, DateStyle),
+}
+
+impl Value {
+    // Parses `text` as a value of `kind`: `text`, `number`, `percent`,
+    // `currency` (`12.50 EUR`), `date` or `long-date` (`2026-01-31`).
+    pub fn parse(kind: &str, text: &str) -> Result<Value, String> {
+        let number = |text: &str| {
+            text.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number `{}`", text))
+        };
+        match kind {
+            "text" => Ok(Value::Text(text.to_string())),
+            "number" => number(text).map(Value::Number),
+            "percent" => number(text).map(Value::Percent),
+            "currency" => match text.trim().rfind(' ') {
+                Some(at) => Ok(Value::Currency(
+                    number(&text.trim()[..at])?,
+                    text.trim()[at + 1..].parse()?,
+                )),
+                None => Err(format!("invalid amount `{}`, expected AMOUNT CODE", text)),
+            },
+            "date" => Ok(Value::Date(text.parse()?, DateStyle::Short)),
+            "long-date" => Ok(Value::Date(text.parse()?, DateStyle::Long)),
+            _ => Err(format!("unknown argument type `{}`", kind)),
+        }
+    }
+
+    // The value as `conventions` write it.
+    pub fn format_with(&self, conventions: &Conventions) -> Cow<'_, str> {
+        match *self {
+            Value::Text(ref text) => Cow::Borrowed(text),
+            Value::Number(value) => Cow::Owned(conventions.number(value)),
+            Value::Percent(value) => Cow::Owned(conventions.percent(value)),
+            Value::Currency(amount, ref currency) => {
+                Cow::Owned(conventions.currency(amount, currency))
+            }
+            Value::Date(date, style) => Cow::Owned(conventions.date(date, style)),
+        }
+    }
+}
+
+// Without a locale, values take their locale-neutral forms.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format_with(&conventions::ROOT))
+    }
+}
+
+impl Args {
+    pub fn new() -> Args {
+        Args::default()
+    }
+
+    pub fn with<T: fmt::Display>(mut self, name: &str, value: T) -> Args {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set<T: fmt::Display>(&mut self, name: &str, value: T) {
+        self.set_value(name, Value::Text(value.to_string()));
+    }
+
+    pub fn with_value(mut self, name: &str, value: Value) -> Args {
+        self.set_value(name, value);
+        self
+    }
+
+    pub fn set_value(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    // Sets an argument given as `name` or `name:kind` and its text, as the
+    // command line and query strings give them: `points:number` and
+    // `1234.5`. See `Value::parse` for the kinds; `gender:attribute` sets
+    // an attribute instead.
+    pub fn set_parsed(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let (name, kind) = match name.find(':') {
+            Some(at) => (&name[..at], &name[at + 1..]),
+            None => (name, "text"),
+        };
+        if kind == "attribute" {
+            self.set_attribute(name, text);
+        } else {
+            self.set_value(name, Value::parse(kind, text)?);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(|name| name.as_str())
+    }
+
+    // Attributes describe who or what a phrase is about, such as the
+    // addressee's `gender`, for selects to choose variants by. Unlike
+    // arguments, a template need not use them: a language without the
+    // distinction simply ignores it.
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Args {
+        self.set_attribute(name, value);
+        self
+    }
+
+    pub fn set_attribute(&mut self, name: &str, value: &str) {
+        self.attributes.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|value| value.as_str())
+    }
+
+    // The value a select on `name` chooses by: the attribute of that name,
+    // or else the argument in its locale-neutral form.
+    pub fn selector(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.attribute(name)
+            .map(Cow::Borrowed)
+            .or_else(|| self.get(name).map(|value| Cow::Owned(value.to_string())))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    MissingPhrase(String),
+    MissingArgument(String),
+    UnexpectedArgument(String),
+    Syntax { offset: usize, message: String },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FormatError::MissingPhrase(ref key) => write!(f, "no phrase for `{}`", key),
+            FormatError::MissingArgument(ref name) => write!(f, "missing argument `{}`", name),
+            FormatError::UnexpectedArgument(ref name) => {
+                write!(f, "unexpected argument `{}`", name)
+            }
+            FormatError::Syntax {
+                offset,
+                ref message,
+            } => {
+                write!(f, "invalid template at byte {}: {}", offset, message)
+            }
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+// A template split into literal text, `{name}` placeholders and selects.
+// `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Brace(char),
+    Placeholder(&'a str),
+    // `{gender, select, female {...} male {...} other {...}}`: the variant
+    // keyed by the selector's value, or else `other`.
+    Select(&'a str, Vec<(&'a str, Vec<Piece<'a>>)>),
+}
+
+fn parse(template: &str) -> Result<Vec<Piece<'_>>, FormatError> {
+    let mut parser = Parser {
+        template,
+        at: 0,
+        nesting: 0,
+    };
+    parser.pieces(false)
+}
+
+struct Parser<'a> {
+    template: &'a str,
+    at: usize,
+    // Selects open around the current position.
+    nesting: usize,
+}
+
+// Selects nested deeper than this are refused rather than parsed with ever
+// more stack.
+const MAX_NESTING: usize = 64;
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.template[self.at..]
+    }
+
+    fn error(&self, offset: usize, message: &str) -> FormatError {
+        FormatError::Syntax {
+            offset,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    // Reads pieces up to the end of the template or, inside a select
+    // variant, up to the `}` closing the variant, which is left unread.
+    // Within a variant every `}` closes it; `}}` is not an escape there.
+    fn pieces(&mut self, nested: bool) -> Result<Vec<Piece<'a>>, FormatError> {
+        let mut pieces = Vec::new();
+        loop {
+            let rest = self.rest();
+            let at = match rest.find(['{', '}']) {
+                Some(at) => at,
+                None => {
+                    if !rest.is_empty() {
+                        pieces.push(Piece::Text(rest));
+                    }
+                    self.at = self.template.len();
+                    return Ok(pieces);
+                }
+            };
+            if at > 0 {
+                pieces.push(Piece::Text(&rest[..at]));
+            }
+            self.at += at;
+
+            if rest[at..].starts_with('}') && nested {
+                return Ok(pieces);
+            }
+            if rest[at..].starts_with("{{") || rest[at..].starts_with("}}") {
+                pieces.push(Piece::Brace(rest[at..].chars().next().unwrap()));
+                self.at += 2;
+                continue;
+            }
+            if rest[at..].starts_with('}') {
+                return Err(self.error(self.at, "unmatched `}`"));
+            }
+            pieces.push(self.placeholder()?);
+        }
+    }
+
+    // Reads `{name}` or `{name, select, ...}` starting at its `{`.
+    fn placeholder(&mut self) -> Result<Piece<'a>, FormatError> {
+        let start = self.at;
+        self.at += 1;
+        let rest = self.rest();
+        let end = match rest.find(['{', '}', ',']) {
+            Some(end) => end,
+            None => return Err(self.error(start, "unclosed `{`")),
+        };
+        let name = rest[..end].trim();
+        if name.is_empty() || rest[end..].starts_with('{') {
+            return Err(self.error(start, "expected a placeholder name"));
+        }
+        self.at += end + 1;
+        if rest[end..].starts_with('}') {
+            return Ok(Piece::Placeholder(name));
+        }
+
+        let rest = self.rest();
+        let kind = match rest.find(',') {
+            Some(end) if rest[..end].trim() == "select" => {
+                self.at += end + 1;
+                rest[..end].trim()
+            }
+            _ => rest.split(['{', '}', ',']).next().unwrap_or("").trim(),
+        };
+        if kind != "select" {
+            return Err(self.error(
+                start,
+                &format!("expected `{{{}, select, ...}}`, found `{}`", name, kind),
+            ));
+        }
+
+        if self.nesting >= MAX_NESTING {
+            return Err(self.error(start, "selects nested too deeply"));
+        }
+        self.nesting += 1;
+        let mut variants = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error(start, "unclosed `{`"));
+            }
+            if rest.starts_with('}') {
+                self.at += 1;
+                break;
+            }
+            let length = rest
+                .find(|c: char| c.is_whitespace() || c == '{' || c == '}')
+                .unwrap_or(rest.len());
+            let key = &rest[..length];
+            self.at += length;
+            self.skip_whitespace();
+            if length == 0 || !self.rest().starts_with('{') {
+                return Err(self.error(self.at, "expected a variant such as `other {...}`"));
+            }
+            self.at += 1;
+            let pieces = self.pieces(true)?;
+            if !self.rest().starts_with('}') {
+                return Err(self.error(start, "unclosed `{`"));
+            }
+            self.at += 1;
+            variants.push((key, pieces));
+        }
+        self.nesting -= 1;
+
+        if !variants.iter().any(|variant| variant.0 == "other") {
+            return Err(self.error(start, "a select needs an `other` variant"));
+        }
+        Ok(Piece::Select(name, variants))
+    }
+}
+
+// Whether `name` is a placeholder or selector anywhere in `pieces`.
+fn uses(pieces: &[Piece], name: &str) -> bool {
+    pieces.iter().any(|piece| match *piece {
+        Piece::Placeholder(placeholder) => placeholder == name,
+        Piece::Select(selector, ref variants) => {
+            selector == name || variants.iter().any(|variant| uses(&variant.1, name))
+        }
+        _ => false,
+    })
+}
+
+fn collect_placeholders(pieces: &[Piece], names: &mut Vec<String>) {
+    for piece in pieces {
+        match *piece {
+            Piece::Placeholder(name) if !names.iter().any(|seen| seen == name) => {
+                names.push(name.to_string())
+            }
+            Piece::Select(_, ref variants) => {
+                for variant in variants {
+                    collect_placeholders(&variant.1, names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The distinct placeholder names used by `template`, in any variant of its
+// selects, in order of first use. Selectors are not included.
+pub fn placeholders(template: &str) -> Result<Vec<String>, FormatError> {
+    let mut names = Vec::new();
+    collect_placeholders(&parse(template)?, &mut names);
+    Ok(names)
+}
+
+// The distinct names `template` selects variants by, such as `gender`.
+pub fn selectors(template: &str) -> Result<Vec<String>, FormatError> {
+    fn collect(pieces: &[Piece], names: &mut Vec<String>) {
+        for piece in pieces {
+            if let Piece::Select(name, ref variants) = *piece {
+                if !names.iter().any(|seen| seen == name) {
+                    names.push(name.to_string());
+                }
+                for variant in variants {
+                    collect(&variant.1, names);
+                }
+            }
+        }
+    }
+    let mut names = Vec::new();
+    collect(&parse(template)?, &mut names);
+    Ok(names)
+}
+
+// Rewrites the literal text of `template` with `rewrite`, keeping its
+// placeholders and selects; each variant's text is rewritten on its own.
+// `rewrite` is given each run of text as it reads, with `{{` and `}}`
+// unescaped, and the braces in what it returns are escaped again.
+pub fn map_text<F>(template: &str, mut rewrite: F) -> Result<String, FormatError>
+where
+    F: FnMut(&str) -> String,
+{
+    let pieces = parse(template)?;
+    let mut out = String::with_capacity(template.len());
+    write_text(&pieces, &mut rewrite, &mut out);
+    Ok(out)
+}
+
+fn write_text<F>(pieces: &[Piece], rewrite: &mut F, out: &mut String)
+where
+    F: FnMut(&str) -> String,
+{
+    let mut run = String::new();
+    for piece in pieces {
+        match *piece {
+            Piece::Text(text) => run.push_str(text),
+            Piece::Brace(brace) => run.push(brace),
+            Piece::Placeholder(name) => {
+                flush_text(&mut run, rewrite, out);
+                out.push('{');
+                out.push_str(name);
+                out.push('}');
+            }
+            Piece::Select(name, ref variants) => {
+                flush_text(&mut run, rewrite, out);
+                out.push('{');
+                out.push_str(name);
+                out.push_str(", select,");
+                for variant in variants {
+                    out.push(' ');
+                    out.push_str(variant.0);
+                    out.push_str(" {");
+                    write_text(&variant.1, rewrite, out);
+                    out.push('}');
+                }
+                out.push('}');
+            }
+        }
+    }
+    flush_text(&mut run, rewrite, out);
+}
+
+fn flush_text<F>(run: &mut String, rewrite: &mut F, out: &mut String)
+where
+    F: FnMut(&str) -> String,
+{
+    if run.is_empty() {
+        return;
+    }
+    for c in rewrite(run).chars() {
+        if c == '{' || c == '}' {
+            out.push(c);
+        }
+        out.push(c);
+    }
+    run.clear();
+}
+
+// Fills in `template` from `args`. Every placeholder must have an argument
+// and every argument must be used by some placeholder.
+// Typed arguments take their locale-neutral forms.
+pub fn format(template: &str, args: &Args) -> Result<String, FormatError> {
+    substitute(template, args, None, &conventions::ROOT)
+}
+
+// Like `format`, for text running in `direction`: arguments are wrapped in
+// Unicode bidi isolates wherever their direction could otherwise reorder
+// the text around them, such as a Latin name inside Arabic.
+pub fn format_directional(
+    template: &str,
+    args: &Args,
+    direction: Direction,
+) -> Result<String, FormatError> {
+    substitute(template, args, Some(direction), &conventions::ROOT)
+}
+
+// Formats a phrase served by `locale`: numbers, percentages, amounts and
+// dates are written by its conventions, and arguments are isolated as
+// `format_directional` does for its direction.
+pub fn format_localized(
+    template: &str,
+    args: &Args,
+    locale: &Locale,
+) -> Result<String, FormatError> {
+    substitute(
+        template,
+        args,
+        Some(direction::of(locale)),
+        conventions::of(locale),
+    )
+}
+
+fn substitute(
+    template: &str,
+    args: &Args,
+    direction: Option<Direction>,
+    conventions: &Conventions,
+) -> Result<String, FormatError> {
+    let pieces = parse(template)?;
+
+    for name in args.names() {
+        if !uses(&pieces, name) {
+            return Err(FormatError::UnexpectedArgument(name.to_string()));
+        }
+    }
+
+    let mut out = String::with_capacity(template.len());
+    write(&pieces, args, direction, conventions, &mut out)?;
+    Ok(out)
+}
+
+fn write(
+    pieces: &[Piece],
+    args: &Args,
+    direction: Option<Direction>,
+    conventions: &Conventions,
+    out: &mut String,
+) -> Result<(), FormatError> {
+    for piece in pieces {
+        match *piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Brace(brace) => out.push(brace),
+            Piece::Placeholder(name) => match args.get(name) {
+                Some(value) => {
+                    let value = value.format_with(conventions);
+                    match direction {
+                        Some(direction) if direction::needs_isolation(direction, &value) => {
+                            out.push_str(&direction::isolate(&value))
+                        }
+                        _ => out.push_str(&value),
+                    }
+                }
+                None => return Err(FormatError::MissingArgument(name.to_string())),
+            },
+            Piece::Select(name, ref variants) => {
+                let selected = args.selector(name);
+                let variant = variants
+                    .iter()
+                    .find(|variant| Some(variant.0) == selected.as_deref())
+                    .or_else(|| variants.iter().find(|variant| variant.0 == "other"));
+                if let Some(variant) = variant {
+                    write(&variant.1, args, direction, conventions, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::env;
+
+use locale::Locale;
+use pseudo::Pseudo;
+
+// One entry of an `Accept-Language` header. A `locale` of `None` is the
+// `*` wildcard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preference {
+    pub locale: Option<Locale>,
+    pub quality: f32,
+}
+
+// The locale chosen for a request, with the quality the client gave the
+// preference it satisfied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Negotiated {
+    pub locale: Locale,
+    pub quality: f32,
+}
+
+// Parses an `Accept-Language` value such as `ja;q=0.9, en;q=0.8` into
+// preferences, highest quality first. Ranges that are not language tags
+// and malformed quality values are skipped rather than failing the whole
+// header.
+pub fn parse_accept_language(header: &str) -> Vec<Preference> {
+    let mut preferences: Vec<Preference> = header.split(',').filter_map(parse_range).collect();
+    // A stable sort keeps the client's order between equal qualities.
+    preferences.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+    preferences
+}
+
+fn parse_range(range: &str) -> Option<Preference> {
+    let mut parts = range.split(';');
+    let tag = parts.next()?.trim();
+    if tag.is_empty() {
+        return None;
+    }
+
+    let mut quality = 1.0;
+    for parameter in parts {
+        let parameter = parameter.trim();
+        if let Some(value) = parameter
+            .strip_prefix("q=")
+            .or_else(|| parameter.strip_prefix("Q="))
+        {
+            quality = value.trim().parse().ok()?;
+            if !(0.0..=1.0).contains(&quality) {
+                return None;
+            }
+        }
+    }
+
+    let locale = if tag == "*" {
+        None
+    } else {
+        Some(parse_tag(tag)?)
+    };
+    Some(Preference { locale, quality })
+}
+
+// Reads a BCP 47 tag leniently: script and variant subtags such as the
+// `Hant` of `zh-Hant-TW` are dropped, keeping the language and region.
+fn parse_tag(tag: &str) -> Option<Locale> {
+    if let Ok(locale) = tag.parse() {
+        return Some(locale);
+    }
+    let mut subtags = tag.split(['-', '_']);
+    let language = subtags.next()?;
+    let locale = language.parse().ok()?;
+    Some(
+        subtags
+            .find_map(|subtag| format!("{}-{}", language, subtag).parse().ok())
+            .unwrap_or(locale),
+    )
+}
+
+// Parses a POSIX locale such as `ja_JP.UTF-8` or `de_DE@euro`. The `C` and
+// `POSIX` locales name no language and give `None`.
+pub fn parse_posix(value: &str) -> Option<Locale> {
+    let name = value.split(['.', '@']).next().unwrap_or("");
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    parse_tag(name)
+}
+
+// The locale the environment asks for, from the first of `LC_ALL`,
+// `LC_MESSAGES` and `LANG` that is set, as POSIX orders them.
+#[cfg(feature = "std")]
+pub fn from_env() -> Option<Locale> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_posix(&value))
+}
+
+// Picks the best of `available` for `preferences`. Each preference, best
+// first, matches the same locale, then its language alone (`ja-JP` is
+// served by `ja`), then a regional locale of its language (`en` by
+// `en-GB`). The wildcard matches `default`. Locales given a quality of zero
+// are never chosen, and the pseudo-locales only when asked for by name:
+// Arabic readers are not served mirrored English from `ar-XB`.
+pub fn negotiate<'a, I>(
+    preferences: &[Preference],
+    available: I,
+    default: &Locale,
+) -> Option<Negotiated>
+where
+    I: IntoIterator<Item = &'a Locale>,
+{
+    let refused = |locale: &Locale| {
+        preferences.iter().any(|preference| {
+            preference.quality == 0.0 && preference.locale.as_ref() == Some(locale)
+        })
+    };
+    let available: Vec<&Locale> = available
+        .into_iter()
+        .filter(|&locale| !refused(locale))
+        .collect();
+
+    for preference in preferences
+        .iter()
+        .filter(|preference| preference.quality > 0.0)
+    {
+        let wanted = match preference.locale {
+            Some(ref locale) => locale,
+            None => match available.iter().find(|&&locale| locale == default) {
+                Some(&locale) => locale,
+                None => continue,
+            },
+        };
+        let language = Locale::new(wanted.language());
+        let related = || {
+            available
+                .iter()
+                .filter(|&&locale| Pseudo::from_locale(locale).is_none())
+        };
+        let found = available
+            .iter()
+            .find(|&&locale| locale == wanted)
+            .or_else(|| related().find(|&&locale| *locale == language))
+            .or_else(|| related().find(|&&locale| locale.language() == wanted.language()));
+        if let Some(&locale) = found {
+            return Some(Negotiated {
+                locale: locale.clone(),
+                quality: preference.quality,
+            });
+        }
+    }
+    None
+}
@@ -0,0 +1,150 @@
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+#[cfg(feature = "std")]
+use json::{self, Object};
+use locale::Locale;
+use phrasebook::PhraseKey;
+
+// How a lookup turned out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome<'a> {
+    // Served in the requested language, by this locale.
+    Hit(&'a Locale),
+    // The requested language had no phrase; this other locale served it.
+    Fallback(&'a Locale),
+    // No locale in the fallback chain had the phrase.
+    Miss,
+}
+
+// One phrase lookup, reported to an `Observer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event<'a> {
+    pub key: PhraseKey,
+    pub requested: &'a Locale,
+    pub outcome: Outcome<'a>,
+}
+
+impl<'a> Event<'a> {
+    // Classifies a lookup of `key` for `requested` by the locale that
+    // served it, if any.
+    pub fn new(key: PhraseKey, requested: &'a Locale, served: Option<&'a Locale>) -> Event<'a> {
+        let outcome = match served {
+            Some(served) if served.language() == requested.language() => Outcome::Hit(served),
+            Some(served) => Outcome::Fallback(served),
+            None => Outcome::Miss,
+        };
+        Event {
+            key,
+            requested,
+            outcome,
+        }
+    }
+}
+
+// Receives every lookup made through a `Resolver` or `PhraseRegistry` it is
+// attached to. Observers are shared between threads and called on the
+// lookup path, so they should be quick.
+pub trait Observer: fmt::Debug + Send + Sync {
+    fn observe(&self, event: &Event);
+}
+
+// Lookup counts for one key in one requested locale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub fallbacks: u64,
+    pub misses: u64,
+}
+
+impl Stats {
+    pub fn total(&self) -> u64 {
+        self.hits + self.fallbacks + self.misses
+    }
+}
+
+// An observer that counts hits, fallbacks and misses per requested locale
+// and key, in memory.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct Counter {
+    counts: Mutex<BTreeMap<(Locale, PhraseKey), Stats>>,
+}
+
+#[cfg(feature = "std")]
+impl Counter {
+    pub fn new() -> Counter {
+        Counter::default()
+    }
+
+    // The counts so far, ordered by locale and key.
+    pub fn stats(&self) -> Vec<(Locale, PhraseKey, Stats)> {
+        self.counts
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .map(|(&(ref locale, key), &stats)| (locale.clone(), key, stats))
+            .collect()
+    }
+
+    pub fn get(&self, locale: &Locale, key: PhraseKey) -> Stats {
+        self.counts
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&(locale.clone(), key))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // The lookups that were not served in the requested language: the
+    // phrases a locale is missing.
+    pub fn missing(&self) -> Vec<(Locale, PhraseKey, Stats)> {
+        self.stats()
+            .into_iter()
+            .filter(|entry| entry.2.fallbacks + entry.2.misses > 0)
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.counts
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+
+    // The counts as a JSON array of
+    // `{"locale", "key", "hits", "fallbacks", "misses"}` objects.
+    pub fn to_json(&self) -> String {
+        json::array(self.stats().into_iter().map(|(locale, key, stats)| {
+            Object::new()
+                .string("locale", &locale.to_string())
+                .string("key", key.name())
+                .number("hits", stats.hits)
+                .number("fallbacks", stats.fallbacks)
+                .number("misses", stats.misses)
+                .to_string()
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Observer for Counter {
+    fn observe(&self, event: &Event) {
+        let mut counts = self.counts.lock().unwrap_or_else(|err| err.into_inner());
+        let stats = counts
+            .entry((event.requested.clone(), event.key))
+            .or_default();
+        match event.outcome {
+            Outcome::Hit(_) => stats.hits += 1,
+            Outcome::Fallback(_) => stats.fallbacks += 1,
+            Outcome::Miss => stats.misses += 1,
+        }
+    }
+}
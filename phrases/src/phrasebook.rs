@@ -0,0 +1,90 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use locale::Locale;
+use message;
+use plural::{self, PluralCategory};
+use register::Register;
+
+// Every phrase the crate knows how to say, independent of language: one
+// variant per key of the reference catalog, `catalogs/en.catalog`. The enum
+// and its `all`, `name` and `is_plural` methods are generated by the build
+// script.
+include!(concat!(env!("OUT_DIR"), "/phrase_key.rs"));
+
+impl PhraseKey {
+    pub fn from_name(name: &str) -> Option<PhraseKey> {
+        PhraseKey::all()
+            .iter()
+            .cloned()
+            .find(|key| key.name() == name)
+    }
+
+    // Accepts a full key (`greetings.hello`) or just its last part (`hello`).
+    pub fn find(name: &str) -> Option<PhraseKey> {
+        PhraseKey::from_name(name).or_else(|| {
+            PhraseKey::all()
+                .iter()
+                .cloned()
+                .find(|key| key.name().rsplit('.').next() == Some(name))
+        })
+    }
+
+    // The placeholders the key takes in the reference catalog, across all
+    // of its register and plural forms, sorted. A plural phrase's `count`
+    // is among them.
+    pub fn placeholders(&self) -> Vec<String> {
+        let english = Locale::new("en");
+        let book = match ::phrasebook(&english) {
+            Some(book) => book,
+            None => return Vec::new(),
+        };
+        let mut forms = Vec::new();
+        if self.is_plural() {
+            forms.extend(
+                plural::categories(&english)
+                    .iter()
+                    .filter_map(|&category| book.plural(*self, category)),
+            );
+        } else {
+            forms.extend(book.phrase(*self));
+            forms.extend(
+                Register::all()
+                    .iter()
+                    .filter_map(|&register| book.phrase_in(*self, register)),
+            );
+        }
+
+        let mut names: Vec<String> = forms
+            .iter()
+            .flat_map(|form| message::placeholders(form).unwrap_or_default())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+// A set of phrases for a single locale. `phrase` returns `None` when the
+// locale has no translation for `key`.
+pub trait Phrasebook {
+    fn locale(&self) -> Locale;
+
+    fn phrase(&self, key: PhraseKey) -> Option<String>;
+
+    // The form of `key` specific to `register`. `None` means the neutral
+    // form from `phrase` serves every register.
+    fn phrase_in(&self, _key: PhraseKey, _register: Register) -> Option<String> {
+        None
+    }
+
+    // The form of `key` for one plural category. Phrasebooks without plural
+    // forms serve their single form as `Other`.
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        if category == PluralCategory::Other {
+            self.phrase(key)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::string::String;
+use core::fmt;
+
+use locale::Locale;
+use phrasebook::{PhraseKey, Phrasebook};
+
+// The CLDR plural categories. Each language uses a subset; every language
+// uses `Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn all() -> &'static [PluralCategory] {
+        &[
+            PluralCategory::Zero,
+            PluralCategory::One,
+            PluralCategory::Two,
+            PluralCategory::Few,
+            PluralCategory::Many,
+            PluralCategory::Other,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PluralCategory> {
+        PluralCategory::all()
+            .iter()
+            .cloned()
+            .find(|category| category.name() == name)
+    }
+}
+
+impl fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Which family of CLDR cardinal rules a language follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    // ja, zh, ko: no plural distinction.
+    Invariant,
+    // en, de, es, ...: 1 is `One`, everything else `Other`.
+    OneOther,
+    // fr, pt: 0 and 1 are both `One`.
+    ZeroOneOther,
+    // ru, uk: `One`, `Few` and `Many` by the last digits.
+    EastSlavic,
+    // ar: every category.
+    Arabic,
+}
+
+fn rule(locale: &Locale) -> Rule {
+    match locale.language() {
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" => Rule::Invariant,
+        "fr" | "pt" => Rule::ZeroOneOther,
+        "ru" | "uk" | "be" => Rule::EastSlavic,
+        "ar" => Rule::Arabic,
+        _ => Rule::OneOther,
+    }
+}
+
+// The plural category `count` falls in for `locale`. Languages without
+// specific rules use the English ones.
+pub fn category(locale: &Locale, count: u64) -> PluralCategory {
+    match rule(locale) {
+        Rule::Invariant => PluralCategory::Other,
+        Rule::OneOther => match count {
+            1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        },
+        Rule::ZeroOneOther => match count {
+            0 | 1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        },
+        Rule::EastSlavic => match (count % 10, count % 100) {
+            (1, rem) if rem != 11 => PluralCategory::One,
+            (2..=4, rem) if !(12..=14).contains(&rem) => PluralCategory::Few,
+            _ => PluralCategory::Many,
+        },
+        Rule::Arabic => match (count, count % 100) {
+            (0, _) => PluralCategory::Zero,
+            (1, _) => PluralCategory::One,
+            (2, _) => PluralCategory::Two,
+            (_, 3..=10) => PluralCategory::Few,
+            (_, 11..=99) => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        },
+    }
+}
+
+// The categories a catalog for `locale` needs forms for.
+pub fn categories(locale: &Locale) -> &'static [PluralCategory] {
+    match rule(locale) {
+        Rule::Invariant => &[PluralCategory::Other],
+        Rule::OneOther | Rule::ZeroOneOther => &[PluralCategory::One, PluralCategory::Other],
+        Rule::EastSlavic => &[
+            PluralCategory::One,
+            PluralCategory::Few,
+            PluralCategory::Many,
+            PluralCategory::Other,
+        ],
+        Rule::Arabic => PluralCategory::all(),
+    }
+}
+
+// The gettext `Plural-Forms` header equivalent to `locale`'s rules. Form
+// indices follow the order of `categories`.
+pub fn gettext_plural_forms(locale: &Locale) -> &'static str {
+    match rule(locale) {
+        Rule::Invariant => "nplurals=1; plural=0;",
+        Rule::OneOther => "nplurals=2; plural=(n != 1);",
+        Rule::ZeroOneOther => "nplurals=2; plural=(n > 1);",
+        Rule::EastSlavic => {
+            "nplurals=4; plural=(n%10==1 && n%100!=11 ? 0 : \
+             n%10>=2 && n%10<=4 && (n%100<12 || n%100>14) ? 1 : 2);"
+        }
+        Rule::Arabic => {
+            "nplurals=6; plural=(n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : \
+             n%100>=3 && n%100<=10 ? 3 : n%100>=11 ? 4 : 5);"
+        }
+    }
+}
+
+// The form of `key` for `category`, or its `Other` form if `book` has no
+// separate one.
+pub fn select(book: &dyn Phrasebook, key: PhraseKey, category: PluralCategory) -> Option<String> {
+    book.plural(key, category)
+        .or_else(|| book.plural(key, PluralCategory::Other))
+}
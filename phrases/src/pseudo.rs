@@ -0,0 +1,167 @@
+use alloc::string::{String, ToString};
+
+use builtin;
+use locale::Locale;
+use message;
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::PluralCategory;
+use register::Register;
+
+// Pseudo-locales rewrite every English phrase so that untranslated strings
+// and layout problems stand out without waiting for real translations.
+// Only literal text is rewritten, in every variant of a select;
+// placeholders, selectors and variant keys are left intact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pseudo {
+    // `en-XA`: accented, lengthened by about a third and bracketed, so
+    // hard-coded strings look wrong and truncation clips the brackets.
+    Accented,
+    // `ar-XB`: English forced right to left, to check mirrored layouts.
+    Bidi,
+}
+
+const ACCENTED: &[(char, char)] = &[
+    ('a', 'á'),
+    ('b', 'ƀ'),
+    ('c', 'ç'),
+    ('d', 'ð'),
+    ('e', 'é'),
+    ('f', 'ƒ'),
+    ('g', 'ĝ'),
+    ('h', 'ĥ'),
+    ('i', 'î'),
+    ('j', 'ĵ'),
+    ('k', 'ķ'),
+    ('l', 'ļ'),
+    ('m', 'ɱ'),
+    ('n', 'ñ'),
+    ('o', 'ö'),
+    ('p', 'þ'),
+    ('q', 'ǫ'),
+    ('r', 'ŕ'),
+    ('s', 'š'),
+    ('t', 'ţ'),
+    ('u', 'û'),
+    ('v', 'ṽ'),
+    ('w', 'ŵ'),
+    ('x', 'ẋ'),
+    ('y', 'ý'),
+    ('z', 'ž'),
+    ('A', 'Å'),
+    ('B', 'Ɓ'),
+    ('C', 'Ç'),
+    ('D', 'Ð'),
+    ('E', 'É'),
+    ('F', 'Ƒ'),
+    ('G', 'Ĝ'),
+    ('H', 'Ĥ'),
+    ('I', 'Î'),
+    ('J', 'Ĵ'),
+    ('K', 'Ķ'),
+    ('L', 'Ļ'),
+    ('M', 'Ṁ'),
+    ('N', 'Ñ'),
+    ('O', 'Ö'),
+    ('P', 'Þ'),
+    ('Q', 'Ǫ'),
+    ('R', 'Ŕ'),
+    ('S', 'Š'),
+    ('T', 'Ţ'),
+    ('U', 'Û'),
+    ('V', 'Ṽ'),
+    ('W', 'Ŵ'),
+    ('X', 'Ẋ'),
+    ('Y', 'Ý'),
+    ('Z', 'Ž'),
+];
+
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+const RIGHT_TO_LEFT_OVERRIDE: char = '\u{202E}';
+const POP_DIRECTIONAL_FORMATTING: char = '\u{202C}';
+
+impl Pseudo {
+    pub fn all() -> &'static [Pseudo] {
+        &[Pseudo::Accented, Pseudo::Bidi]
+    }
+
+    pub fn tag(&self) -> &'static str {
+        match *self {
+            Pseudo::Accented => "en-XA",
+            Pseudo::Bidi => "ar-XB",
+        }
+    }
+
+    pub fn from_locale(locale: &Locale) -> Option<Pseudo> {
+        Pseudo::all()
+            .iter()
+            .cloned()
+            .find(|pseudo| pseudo.locale() == *locale)
+    }
+
+    // Rewrites a phrase template, leaving its placeholders alone. A
+    // malformed template keeps its text, so the mistake still shows.
+    pub fn transform(&self, template: &str) -> String {
+        let mut letters = 0;
+        let out = message::map_text(template, |text| {
+            letters += text.chars().filter(|c| c.is_alphabetic()).count();
+            self.run(text)
+        })
+        .unwrap_or_else(|_| template.to_string());
+
+        match *self {
+            Pseudo::Accented => {
+                let padding = "!".repeat((letters * 3).div_ceil(10).max(1));
+                format!("[{}{} ]", out, padding)
+            }
+            Pseudo::Bidi => format!("{}{}{}", RIGHT_TO_LEFT_MARK, out, RIGHT_TO_LEFT_MARK),
+        }
+    }
+
+    // Rewrites a run of literal text between placeholders.
+    fn run(&self, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        match *self {
+            Pseudo::Accented => text.chars().map(accent).collect(),
+            Pseudo::Bidi => format!(
+                "{}{}{}",
+                RIGHT_TO_LEFT_OVERRIDE, text, POP_DIRECTIONAL_FORMATTING
+            ),
+        }
+    }
+}
+
+fn accent(c: char) -> char {
+    ACCENTED
+        .iter()
+        .find(|entry| entry.0 == c)
+        .map_or(c, |entry| entry.1)
+}
+
+// Pseudo-locales carry every form English has. A form English lacks, such
+// as the Arabic plural categories of `ar-XB`, takes its `Other` form.
+impl Phrasebook for Pseudo {
+    fn locale(&self) -> Locale {
+        self.tag().parse().expect("pseudo-locale tags are valid")
+    }
+
+    fn phrase(&self, key: PhraseKey) -> Option<String> {
+        builtin::EN.phrase(key).map(|text| self.transform(&text))
+    }
+
+    fn phrase_in(&self, key: PhraseKey, register: Register) -> Option<String> {
+        builtin::EN
+            .phrase_in(key, register)
+            .map(|text| self.transform(&text))
+    }
+
+    fn plural(&self, key: PhraseKey, category: PluralCategory) -> Option<String> {
+        let text = match builtin::EN.plural(key, category) {
+            Some(text) => Some(text),
+            None if key.is_plural() => builtin::EN.plural(key, PluralCategory::Other),
+            None => None,
+        };
+        text.map(|text| self.transform(&text))
+    }
+}
@@ -0,0 +1,73 @@
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use phrasebook::{PhraseKey, Phrasebook};
+
+// How formally a phrase addresses the listener. Catalogs store a register's
+// form as `key@register` (`hello@casual = やあ`); the plain `key` entry is
+// the neutral form used when a locale has nothing more specific.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Register {
+    Casual,
+    #[default]
+    Polite,
+    Formal,
+}
+
+impl Register {
+    pub fn all() -> &'static [Register] {
+        &[Register::Casual, Register::Polite, Register::Formal]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Register::Casual => "casual",
+            Register::Polite => "polite",
+            Register::Formal => "formal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Register> {
+        Register::all()
+            .iter()
+            .cloned()
+            .find(|register| register.name() == name)
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Register, String> {
+        Register::from_name(name).ok_or_else(|| format!("unknown register `{}`", name))
+    }
+}
+
+// The catalog entry holding `name`'s form for `register`.
+pub fn register_name(name: &str, register: Register) -> String {
+    format!("{}@{}", name, register.name())
+}
+
+// Splits `greetings.hello@casual` into `greetings.hello` and its register.
+pub fn split_register(name: &str) -> (&str, Option<Register>) {
+    match name.rfind('@') {
+        Some(at) => match Register::from_name(&name[at + 1..]) {
+            Some(register) => (&name[..at], Some(register)),
+            None => (name, None),
+        },
+        None => (name, None),
+    }
+}
+
+// The form of `key` for `register`, or its neutral form if `book` has no
+// separate one.
+pub fn select(book: &dyn Phrasebook, key: PhraseKey, register: Register) -> Option<String> {
+    book.phrase_in(key, register).or_else(|| book.phrase(key))
+}
@@ -0,0 +1,383 @@
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::SystemTime;
+
+use catalog::{self, Catalog, CatalogError, Catalogs};
+use fluent;
+use locale::Locale;
+use message::{self, Args, FormatError};
+use observe::{Event, Observer};
+use phrasebook::{PhraseKey, Phrasebook};
+use plural::{self, PluralCategory};
+use register::Register;
+
+// Shares phrases between threads. Readers take a snapshot, an `Arc` clone
+// under a read lock held only for the clone, and look phrases up in it
+// without allocating. Reloading builds the next snapshot before taking the
+// write lock, which it holds just long enough to swap the pointer, so
+// readers never wait for parsing and never see a half-loaded set. A reader
+// arriving during the swap itself does block until the pointer is stored.
+#[derive(Debug)]
+pub struct PhraseRegistry {
+    current: RwLock<Arc<Snapshot>>,
+    source: Mutex<Source>,
+}
+
+#[derive(Debug, Default)]
+struct Source {
+    dir: Option<PathBuf>,
+    files: Files,
+}
+
+// The catalog files in a directory, with each one's modification time,
+// length and content hash as of the last load.
+type Files = BTreeMap<PathBuf, (SystemTime, u64, u64)>;
+
+impl PhraseRegistry {
+    pub fn new(catalogs: &Catalogs) -> PhraseRegistry {
+        PhraseRegistry {
+            current: RwLock::new(Arc::new(Snapshot {
+                default: Locale::new("en"),
+                tables: Snapshot::tables(catalogs),
+                generation: 0,
+                observer: None,
+            })),
+            source: Mutex::new(Source::default()),
+        }
+    }
+
+    pub fn builtin() -> PhraseRegistry {
+        PhraseRegistry::new(&Catalogs::builtin())
+    }
+
+    // A registry of the built-in catalogs overlaid with the catalog files in
+    // `dir`, which `reload_if_changed` watches.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<PhraseRegistry, CatalogError> {
+        let dir = dir.as_ref().to_path_buf();
+        let files = catalog_files(&dir)?;
+        let mut catalogs = Catalogs::builtin();
+        catalogs.load_dir(&dir)?;
+
+        let registry = PhraseRegistry::new(&catalogs);
+        *registry.lock_source() = Source {
+            dir: Some(dir),
+            files,
+        };
+        Ok(registry)
+    }
+
+    pub fn with_default(self, default: Locale) -> PhraseRegistry {
+        let snapshot = self.snapshot();
+        self.swap(Snapshot {
+            default,
+            tables: snapshot.tables.clone(),
+            generation: snapshot.generation,
+            observer: snapshot.observer.clone(),
+        });
+        self
+    }
+
+    // Reports every lookup in this registry's snapshots, present and
+    // future, to `observer`.
+    pub fn with_observer(self, observer: Arc<dyn Observer>) -> PhraseRegistry {
+        let snapshot = self.snapshot();
+        self.swap(Snapshot {
+            default: snapshot.default.clone(),
+            tables: snapshot.tables.clone(),
+            generation: snapshot.generation,
+            observer: Some(observer),
+        });
+        self
+    }
+
+    // The phrases as of now; later reloads do not affect it.
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        self.current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    // Writers queue on the source lock so that no swap is lost to another.
+    fn lock_source(&self) -> MutexGuard<'_, Source> {
+        self.source.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Atomically replaces every phrase with those in `catalogs`.
+    pub fn replace(&self, catalogs: &Catalogs) {
+        let _writer = self.lock_source();
+        self.install(catalogs);
+    }
+
+    fn install(&self, catalogs: &Catalogs) {
+        let current = self.snapshot();
+        self.swap(current.next(Snapshot::tables(catalogs)));
+    }
+
+    // Atomically replaces the phrases of one locale, keeping the others.
+    pub fn replace_catalog(&self, catalog: &Catalog) {
+        let _writer = self.lock_source();
+        let current = self.snapshot();
+        let mut tables: Vec<Table> = current
+            .tables
+            .iter()
+            .filter(|table| table.locale != *catalog.locale())
+            .cloned()
+            .collect();
+        let mut pool = tables
+            .iter()
+            .flat_map(|table| table.entries.values().cloned())
+            .collect();
+        tables.push(Table::build(catalog, &mut pool));
+
+        self.swap(current.next(tables));
+    }
+
+    fn swap(&self, next: Snapshot) {
+        *self.current.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(next);
+    }
+
+    // Reloads the watched directory if a catalog file in it was added,
+    // removed or changed since the last load. On error the current phrases
+    // stay in place.
+    pub fn reload_if_changed(&self) -> Result<bool, CatalogError> {
+        let mut source = self.lock_source();
+        let dir = match source.dir {
+            Some(ref dir) => dir.clone(),
+            None => return Ok(false),
+        };
+        let files = catalog_files(&dir)?;
+        if files == source.files {
+            return Ok(false);
+        }
+
+        let mut catalogs = Catalogs::builtin();
+        catalogs.load_dir(&dir)?;
+        self.install(&catalogs);
+        source.files = files;
+        Ok(true)
+    }
+}
+
+impl Default for PhraseRegistry {
+    fn default() -> PhraseRegistry {
+        PhraseRegistry::builtin()
+    }
+}
+
+// Lists the catalog files in `dir`. A file rewritten within the clock's
+// resolution, even to the same length, still differs in its hash.
+fn catalog_files(dir: &Path) -> Result<Files, CatalogError> {
+    let mut files = Files::new();
+    for entry in fs::read_dir(dir).map_err(CatalogError::Io)? {
+        let path = entry.map_err(CatalogError::Io)?.path();
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if extension != Some(catalog::EXTENSION) && extension != Some(fluent::EXTENSION) {
+            continue;
+        }
+        let metadata = fs::metadata(&path).map_err(CatalogError::Io)?;
+        let modified = metadata.modified().map_err(CatalogError::Io)?;
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&fs::read(&path).map_err(CatalogError::Io)?);
+        files.insert(path, (modified, metadata.len(), hasher.finish()));
+    }
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Form {
+    Neutral,
+    Register(Register),
+    Plural(PluralCategory),
+}
+
+#[derive(Debug, Clone)]
+struct Table {
+    locale: Locale,
+    entries: HashMap<(PhraseKey, Form), Arc<str>>,
+}
+
+impl Table {
+    // Interns every text through `pool`, so a string repeated across keys
+    // or locales is stored once.
+    fn build(catalog: &Catalog, pool: &mut HashSet<Arc<str>>) -> Table {
+        let mut entries = HashMap::new();
+        let mut insert = |slot: (PhraseKey, Form), text: Option<String>| {
+            if let Some(text) = text {
+                let text = match pool.get(text.as_str()) {
+                    Some(interned) => interned.clone(),
+                    None => {
+                        let interned: Arc<str> = Arc::from(text);
+                        pool.insert(interned.clone());
+                        interned
+                    }
+                };
+                entries.insert(slot, text);
+            }
+        };
+
+        for &key in PhraseKey::all() {
+            insert((key, Form::Neutral), catalog.phrase(key));
+            for &register in Register::all() {
+                insert(
+                    (key, Form::Register(register)),
+                    catalog.phrase_in(key, register),
+                );
+            }
+            if key.is_plural() {
+                for &category in PluralCategory::all() {
+                    insert((key, Form::Plural(category)), catalog.plural(key, category));
+                }
+            }
+        }
+        Table {
+            locale: catalog.locale().clone(),
+            entries,
+        }
+    }
+
+    fn get(&self, key: PhraseKey, form: Form) -> Option<&str> {
+        self.entries.get(&(key, form)).map(|text| &**text)
+    }
+}
+
+// A phrase borrowed from a snapshot, with the locale that served it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phrase<'a> {
+    pub text: &'a str,
+    pub locale: &'a Locale,
+}
+
+// An immutable set of phrases. Lookups follow the same fallback chain as
+// `Resolver`: the full tag, its language, then the default locale.
+#[derive(Debug)]
+pub struct Snapshot {
+    default: Locale,
+    tables: Vec<Table>,
+    generation: u64,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl Snapshot {
+    fn tables(catalogs: &Catalogs) -> Vec<Table> {
+        let mut pool = HashSet::new();
+        catalogs
+            .iter()
+            .map(|catalog| Table::build(catalog, &mut pool))
+            .collect()
+    }
+
+    // The snapshot that replaces this one, holding `tables`.
+    fn next(&self, tables: Vec<Table>) -> Snapshot {
+        Snapshot {
+            default: self.default.clone(),
+            tables,
+            generation: self.generation + 1,
+            observer: self.observer.clone(),
+        }
+    }
+
+    // Counts the swaps since the registry was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn default_locale(&self) -> &Locale {
+        &self.default
+    }
+
+    pub fn locales(&self) -> impl Iterator<Item = &Locale> {
+        self.tables.iter().map(|table| &table.locale)
+    }
+
+    fn table(&self, language: &str, region: Option<&str>) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|table| table.locale.language() == language && table.locale.region() == region)
+    }
+
+    fn find<'a, F>(&'a self, locale: &Locale, key: PhraseKey, get: F) -> Option<Phrase<'a>>
+    where
+        F: Fn(&'a Table) -> Option<&'a str>,
+    {
+        let chain = [
+            (locale.language(), locale.region()),
+            (locale.language(), None),
+            (self.default.language(), self.default.region()),
+        ];
+        let found = chain.iter().find_map(|&(language, region)| {
+            let table = self.table(language, region)?;
+            get(table).map(|text| Phrase {
+                text,
+                locale: &table.locale,
+            })
+        });
+        if let Some(ref observer) = self.observer {
+            observer.observe(&Event::new(key, locale, found.map(|phrase| phrase.locale)));
+        }
+        found
+    }
+
+    pub fn phrase(&self, locale: &Locale, key: PhraseKey) -> Option<&str> {
+        self.resolve(locale, key).map(|phrase| phrase.text)
+    }
+
+    pub fn resolve(&self, locale: &Locale, key: PhraseKey) -> Option<Phrase<'_>> {
+        self.find(locale, key, |table| table.get(key, Form::Neutral))
+    }
+
+    // Like `resolve`, preferring each locale's form for `register`.
+    pub fn resolve_with_register(
+        &self,
+        locale: &Locale,
+        key: PhraseKey,
+        register: Register,
+    ) -> Option<Phrase<'_>> {
+        self.find(locale, key, |table| {
+            table
+                .get(key, Form::Register(register))
+                .or_else(|| table.get(key, Form::Neutral))
+        })
+    }
+
+    // The form of a count-dependent phrase for `count`, chosen by each
+    // candidate locale's own plural rules.
+    pub fn resolve_count(&self, locale: &Locale, key: PhraseKey, count: u64) -> Option<Phrase<'_>> {
+        self.find(locale, key, |table| {
+            let category = plural::category(&table.locale, count);
+            table
+                .get(key, Form::Plural(category))
+                .or_else(|| table.get(key, Form::Plural(PluralCategory::Other)))
+                .or_else(|| table.get(key, Form::Neutral))
+        })
+    }
+
+    // Fills in `key`'s placeholders, borrowing the phrase when it has none.
+    pub fn format(
+        &self,
+        locale: &Locale,
+        key: PhraseKey,
+        args: &Args,
+    ) -> Result<Cow<'_, str>, FormatError> {
+        let phrase = self
+            .resolve(locale, key)
+            .ok_or_else(|| FormatError::MissingPhrase(key.name().to_string()))?;
+        format_text(phrase.text, args, phrase.locale)
+    }
+}
+
+fn format_text<'a>(
+    text: &'a str,
+    args: &Args,
+    locale: &Locale,
+) -> Result<Cow<'a, str>, FormatError> {
+    if !text.contains(['{', '}']) && args.names().next().is_none() {
+        return Ok(Cow::Borrowed(text));
+    }
+    message::format_localized(text, args, locale).map(Cow::Owned)
+}
@@ -0,0 +1,426 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use direction;
+use json::{self, Object};
+use locale::Locale;
+use message::{self, Args, Value};
+use observe::Counter;
+use phrasebook::PhraseKey;
+use register::Register;
+use registry::{PhraseRegistry, Snapshot};
+
+// A small HTTP/1.1 JSON server over a `PhraseRegistry`:
+//
+//     GET /locales
+//     GET /phrases/{locale}/{key}?register=casual&count=3&name=Sam
+//     GET /stats                  (with a counter attached)
+//
+// Query parameters other than `register` and `count` fill in placeholders,
+// typed as on the command line: `points:number=1234.5`. Count-dependent
+// phrases have no registers, so the two cannot be combined.
+// Every response carries an ETag derived from its body, and a request whose
+// `If-None-Match` lists it gets `304 Not Modified`. Each connection serves
+// one request on its own thread, up to `MAX_CONNECTIONS` at a time.
+#[derive(Debug, Clone)]
+pub struct Server {
+    registry: Arc<PhraseRegistry>,
+    stats: Option<Arc<Counter>>,
+}
+
+// The parts of a request the server looks at. Header names are lowercase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+// Requests longer than this are refused; no more than one byte past it is
+// read.
+const MAX_HEAD: usize = 8 * 1024;
+
+// A client that sends nothing for this long is hung up on, so idle
+// connections do not hold their threads.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Connections beyond this many at once get `503 Service Unavailable`.
+const MAX_CONNECTIONS: usize = 256;
+
+impl Request {
+    // Parses a request line and headers, e.g. `GET /locales HTTP/1.1`.
+    pub fn parse(head: &str) -> Option<Request> {
+        let mut lines = head.lines();
+        let mut parts = lines.next()?.split_whitespace();
+        let method = parts.next()?.to_string();
+        let target = parts.next()?;
+        if !parts.next()?.starts_with("HTTP/") {
+            return None;
+        }
+
+        let (path, query) = match target.find('?') {
+            Some(at) => (&target[..at], &target[at + 1..]),
+            None => (target, ""),
+        };
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.find('=') {
+                Some(at) => (decode_query(&pair[..at]), decode_query(&pair[at + 1..])),
+                None => (decode_query(pair), String::new()),
+            })
+            .collect();
+        let headers = lines
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| {
+                let at = line.find(':')?;
+                Some((
+                    line[..at].trim().to_ascii_lowercase(),
+                    line[at + 1..].trim().to_string(),
+                ))
+            })
+            .collect();
+
+        Some(Request {
+            method,
+            path: path.to_string(),
+            query,
+            headers,
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.0 == name)
+            .map(|header| header.1.as_str())
+    }
+}
+
+// Decodes `%XX` escapes in a path segment, where `+` is just a plus.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                hex(bytes[i + 1]).and_then(|high| hex(bytes[i + 2]).map(|low| high * 16 + low))
+            }
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Decodes a query component, where `+` also stands for a space.
+fn decode_query(text: &str) -> String {
+    decode(&text.replace('+', " "))
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Response {
+        Response {
+            status,
+            headers: vec![
+                (
+                    "Content-Type".to_string(),
+                    "application/json; charset=utf-8".to_string(),
+                ),
+                ("Cache-Control".to_string(), "no-cache".to_string()),
+                ("ETag".to_string(), etag(&body)),
+            ],
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, Object::new().string("error", message).to_string())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.0.eq_ignore_ascii_case(name))
+            .map(|header| header.1.as_str())
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            304 => "Not Modified",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    // Writes the response; a `HEAD` request gets the headers alone. A `304`
+    // has no body, so it says nothing about one.
+    pub fn write_to<W: Write>(&self, out: &mut W, head_only: bool) -> io::Result<()> {
+        write!(out, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        for (name, value) in &self.headers {
+            write!(out, "{}: {}\r\n", name, value)?;
+        }
+        if self.status != 304 {
+            write!(out, "Content-Length: {}\r\n", self.body.len())?;
+        }
+        write!(out, "Connection: close\r\n\r\n")?;
+        if !head_only {
+            out.write_all(self.body.as_bytes())?;
+        }
+        out.flush()
+    }
+}
+
+// A strong ETag: the FNV-1a hash of the body, so identical responses share
+// a tag across reloads and restarts.
+fn etag(body: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in body.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("\"{:016x}\"", hash)
+}
+
+fn matches_etag(request: &Request, tag: &str) -> bool {
+    request.header("if-none-match").is_some_and(|value| {
+        value.split(',').any(|candidate| {
+            candidate.trim() == "*" || candidate.trim().trim_start_matches("W/") == tag
+        })
+    })
+}
+
+impl Server {
+    pub fn new(registry: Arc<PhraseRegistry>) -> Server {
+        Server {
+            registry,
+            stats: None,
+        }
+    }
+
+    // Publishes `counter`'s statistics at `/stats`. The counter should be
+    // observing the registry for them to mean anything.
+    pub fn with_stats(mut self, counter: Arc<Counter>) -> Server {
+        self.stats = Some(counter);
+        self
+    }
+
+    pub fn registry(&self) -> &Arc<PhraseRegistry> {
+        &self.registry
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        if request.method != "GET" && request.method != "HEAD" {
+            let mut response =
+                Response::error(405, &format!("method {} is not allowed", request.method));
+            response
+                .headers
+                .push(("Allow".to_string(), "GET, HEAD".to_string()));
+            return response;
+        }
+
+        let snapshot = self.registry.snapshot();
+        let segments: Vec<String> = request.path.split('/').skip(1).map(decode).collect();
+        let segments: Vec<&str> = segments.iter().map(|segment| segment.as_str()).collect();
+        let response = match segments.as_slice() {
+            ["locales"] => locales(&snapshot),
+            ["stats"] => match self.stats {
+                Some(ref counter) => Response::json(200, counter.to_json()),
+                None => Response::error(404, "statistics are not being collected"),
+            },
+            ["phrases", locale, key] => phrase(&snapshot, locale, key, &request.query),
+            _ => Response::error(404, &format!("no such resource `{}`", request.path)),
+        };
+
+        let not_modified = response.status == 200
+            && response
+                .header("ETag")
+                .is_some_and(|tag| matches_etag(request, tag));
+        if not_modified {
+            return Response {
+                status: 304,
+                headers: response
+                    .headers
+                    .into_iter()
+                    .filter(|header| !header.0.eq_ignore_ascii_case("Content-Type"))
+                    .collect(),
+                body: String::new(),
+            };
+        }
+        response
+    }
+
+    // Accepts connections until the listener fails, answering each on its
+    // own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let stream = stream?;
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                active.fetch_sub(1, Ordering::SeqCst);
+                // Without blocking the accept loop on a client that does
+                // not read.
+                let _ = stream.set_nonblocking(true);
+                let _ = Response::error(503, "too many connections").write_to(&mut &stream, false);
+                continue;
+            }
+            let server = self.clone();
+            let slot = Slot(active.clone());
+            thread::spawn(move || {
+                let _slot = slot;
+                // A client that hangs up mid-request has nobody to tell.
+                let _ = server.answer(stream);
+            });
+        }
+        Ok(())
+    }
+
+    fn answer(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?).take(MAX_HEAD as u64 + 1);
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") && !head.ends_with("\n\n") {
+            if reader.read_line(&mut head)? == 0 {
+                break;
+            }
+        }
+        if head.len() > MAX_HEAD {
+            return Response::error(400, "request head too large").write_to(&mut &stream, false);
+        }
+
+        match Request::parse(&head) {
+            Some(request) => self
+                .handle(&request)
+                .write_to(&mut &stream, request.method == "HEAD"),
+            None => Response::error(400, "malformed request").write_to(&mut &stream, false),
+        }
+    }
+}
+
+// One of the `MAX_CONNECTIONS`, given back when its thread ends, even by
+// panicking.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn locales(snapshot: &Snapshot) -> Response {
+    let mut locales: Vec<&Locale> = snapshot.locales().collect();
+    locales.sort();
+    let locales = locales.iter().map(|locale| {
+        Object::new()
+            .string("locale", &locale.to_string())
+            .string("direction", direction::of(locale).name())
+            .to_string()
+    });
+    Response::json(
+        200,
+        Object::new()
+            .string("default", &snapshot.default_locale().to_string())
+            .raw("locales", json::array(locales))
+            .to_string(),
+    )
+}
+
+fn phrase(snapshot: &Snapshot, locale: &str, key: &str, query: &[(String, String)]) -> Response {
+    let requested: Locale = match locale.parse() {
+        Ok(locale) => locale,
+        Err(err) => return Response::error(400, &format!("{}", err)),
+    };
+    let key = match PhraseKey::find(key) {
+        Some(key) => key,
+        None => return Response::error(404, &format!("unknown phrase `{}`", key)),
+    };
+
+    let mut register: Option<Register> = None;
+    let mut count = None;
+    let mut args = Args::new();
+    for (name, value) in query {
+        match name.as_str() {
+            "register" => match value.parse() {
+                Ok(value) => register = Some(value),
+                Err(err) => return Response::error(400, &err),
+            },
+            "count" => match value.parse::<u64>() {
+                Ok(value) => count = Some(value),
+                Err(_) => return Response::error(400, &format!("invalid count `{}`", value)),
+            },
+            _ => {
+                if let Err(err) = args.set_parsed(name, value) {
+                    return Response::error(400, &err);
+                }
+            }
+        }
+    }
+
+    let found = match (count, register) {
+        (Some(_), Some(_)) => {
+            return Response::error(400, "`register` cannot be combined with `count`")
+        }
+        (Some(count), None) => {
+            args.set_value("count", Value::Number(count as f64));
+            snapshot.resolve_count(&requested, key, count)
+        }
+        (None, register) => {
+            snapshot.resolve_with_register(&requested, key, register.unwrap_or_default())
+        }
+    };
+    let found = match found {
+        Some(found) => found,
+        None => {
+            return Response::error(
+                404,
+                &format!("no phrase for `{}` in {}", key.name(), requested),
+            )
+        }
+    };
+    let text = match message::format_localized(found.text, &args, found.locale) {
+        Ok(text) => text,
+        Err(err) => return Response::error(400, &err.to_string()),
+    };
+
+    Response::json(
+        200,
+        Object::new()
+            .string("key", key.name())
+            .string("locale", &requested.to_string())
+            .string("served_by", &found.locale.to_string())
+            .string("direction", direction::of(found.locale).name())
+            .string("text", &text)
+            .to_string(),
+    )
+}
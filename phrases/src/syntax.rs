@@ -0,0 +1,105 @@
+// The line grammar of `.catalog` files. This module is also compiled into
+// the build script, so it may only depend on `core` and `alloc`.
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub line: usize,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl SyntaxError {
+    fn new(line: usize, message: &str) -> SyntaxError {
+        SyntaxError {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// Parses `[section]` headers and `name = value` lines into entries keyed
+// `section.name`, in file order.
+pub fn parse(source: &str) -> Result<Vec<Entry>, SyntaxError> {
+    let (entries, errors) = parse_all(source);
+    let mut seen = BTreeSet::new();
+    let duplicate = entries
+        .iter()
+        .find(|entry| !seen.insert(entry.key.as_str()))
+        .map(|entry| SyntaxError::new(entry.line, &format!("duplicate key `{}`", entry.key)));
+    let first = errors
+        .into_iter()
+        .chain(duplicate)
+        .min_by_key(|error| error.line);
+    match first {
+        Some(error) => Err(error),
+        None => Ok(entries),
+    }
+}
+
+// Parses like `parse`, but reads past malformed lines and keeps every
+// entry, repeated keys included, for tools that report all problems at
+// once.
+pub fn parse_all(source: &str) -> (Vec<Entry>, Vec<SyntaxError>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut section = String::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if !line.ends_with(']') || line.len() < 3 {
+                errors.push(SyntaxError::new(line_number, "malformed section header"));
+                continue;
+            }
+            section = line[1..line.len() - 1].trim().to_string();
+            continue;
+        }
+
+        let (name, value) = match line.find('=') {
+            Some(at) => (line[..at].trim(), line[at + 1..].trim()),
+            None => {
+                errors.push(SyntaxError::new(line_number, "expected `key = value`"));
+                continue;
+            }
+        };
+        if name.is_empty() {
+            errors.push(SyntaxError::new(line_number, "missing key before `=`"));
+            continue;
+        }
+
+        let key = if section.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", section, name)
+        };
+        entries.push(Entry {
+            line: line_number,
+            key,
+            value: value.to_string(),
+        });
+    }
+
+    (entries, errors)
+}
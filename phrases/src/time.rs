@@ -0,0 +1,167 @@
+use alloc::string::String;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use phrasebook::PhraseKey;
+
+// A local wall-clock time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8) -> Option<Time> {
+        if hour < 24 && minute < 60 {
+            Some(Time { hour, minute })
+        } else {
+            None
+        }
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+}
+
+// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+        if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    // 1 for January through 12 for December.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Accepts ISO 8601 calendar dates such as `2026-01-31`.
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Date, String> {
+        let mut parts = text.splitn(3, '-');
+        let date = match (parts.next(), parts.next(), parts.next()) {
+            (Some(year), Some(month), Some(day)) if year.len() == 4 => {
+                match (year.parse(), month.parse(), day.parse()) {
+                    (Ok(year), Ok(month), Ok(day)) => Date::new(year, month, day),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        date.ok_or_else(|| format!("invalid date `{}`, expected YYYY-MM-DD", text))
+    }
+}
+
+// The part of the day a greeting is chosen by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DayPeriod {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl DayPeriod {
+    // Morning runs from 05:00 until noon and afternoon until 18:00; the rest
+    // of the day, night included, is evening.
+    pub fn of(time: Time) -> DayPeriod {
+        match time.hour() {
+            5..=11 => DayPeriod::Morning,
+            12..=17 => DayPeriod::Afternoon,
+            _ => DayPeriod::Evening,
+        }
+    }
+
+    pub fn greeting(&self) -> PhraseKey {
+        match *self {
+            DayPeriod::Morning => PhraseKey::GoodMorning,
+            DayPeriod::Afternoon => PhraseKey::GoodAfternoon,
+            DayPeriod::Evening => PhraseKey::GoodEvening,
+        }
+    }
+}
+
+// A source of the current local time. Tests can stub it with `FixedClock`.
+pub trait Clock {
+    fn now(&self) -> Time;
+}
+
+// Always reports the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub Time);
+
+impl Clock for FixedClock {
+    fn now(&self) -> Time {
+        self.0
+    }
+}
+
+// The system clock, shifted from UTC by a fixed offset since `std` cannot
+// see the local time zone.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SystemClock {
+    offset_minutes: i32,
+}
+
+#[cfg(feature = "std")]
+impl SystemClock {
+    pub fn utc() -> SystemClock {
+        SystemClock::default()
+    }
+
+    pub fn with_offset(offset_minutes: i32) -> SystemClock {
+        SystemClock { offset_minutes }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Time {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or(0);
+        let minutes = (since_epoch / 60 + i64::from(self.offset_minutes)).rem_euclid(24 * 60);
+        Time {
+            hour: (minutes / 60) as u8,
+            minute: (minutes % 60) as u8,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// Terminal display width. `format!("{:>8}", text)` counts chars, so a
+// column holding さようなら (five chars, ten cells wide) or a word with
+// combining accents comes out misaligned; these helpers count cells
+// instead and never cut a character off its combining marks.
+
+use alloc::string::{String, ToString};
+
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036F), // combining diacritical marks
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD), // Hebrew points
+    (0x05BF, 0x05C7),
+    (0x0610, 0x061A), // Arabic marks
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06ED),
+    (0x0E31, 0x0E31), // Thai vowels and tones
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1160, 0x11FF), // Hangul Jamo vowels and finals, joining an initial
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F), // zero-width space and joiners, direction marks
+    (0x202A, 0x202E), // bidi embeddings and overrides
+    (0x2060, 0x2069), // word joiner, bidi isolates
+    (0x20D0, 0x20FF), // combining marks for symbols
+    (0x3099, 0x309A), // combining kana voicing marks
+    (0xFE00, 0xFE0F), // variation selectors
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0xE0100, 0xE01EF),
+];
+
+// East Asian wide and fullwidth characters, and emoji presented as wide.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F), // Hangul Jamo initials
+    (0x2600, 0x27BF), // miscellaneous symbols, dingbats
+    (0x2E80, 0x303E), // CJK radicals, punctuation
+    (0x3041, 0x33FF), // kana, CJK symbols
+    (0x3400, 0x4DBF), // CJK extension A
+    (0x4E00, 0x9FFF), // CJK unified ideographs
+    (0xA000, 0xA4CF), // Yi
+    (0xAC00, 0xD7A3), // Hangul syllables
+    (0xF900, 0xFAFF), // CJK compatibility ideographs
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60), // fullwidth forms
+    (0xFFE0, 0xFFE6),
+    (0x1F1E6, 0x1F1FF), // regional indicators, paired into flags
+    (0x1F300, 0x1F64F), // pictographs, emoticons
+    (0x1F680, 0x1F6FF), // transport and map symbols
+    (0x1F900, 0x1F9FF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let code = c as u32;
+    ranges
+        .iter()
+        .any(|&(start, end)| start <= code && code <= end)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+// Cells taken by `c` on its own: two for wide characters, none for
+// combining marks, format characters and controls, one otherwise.
+pub fn char_width(c: char) -> usize {
+    if c.is_control() || in_ranges(c, ZERO_WIDTH) {
+        0
+    } else if in_ranges(c, WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+// Splits `text` into user-perceived characters: a base character with the
+// combining marks after it, emoji joined by a zero-width joiner, and pairs
+// of regional indicators forming a flag. This covers what phrases contain
+// without the full Unicode segmentation tables.
+pub fn graphemes(text: &str) -> Graphemes<'_> {
+    Graphemes { rest: text }
+}
+
+pub struct Graphemes<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut chars = self.rest.char_indices();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+        let mut joined = false;
+        let mut flag = is_regional_indicator(first);
+
+        for (at, c) in chars {
+            let extends = joined
+                || c == ZERO_WIDTH_JOINER
+                || (char_width(c) == 0 && !c.is_control())
+                || (flag && is_regional_indicator(c));
+            if !extends {
+                break;
+            }
+            flag = false;
+            joined = c == ZERO_WIDTH_JOINER;
+            end = at + c.len_utf8();
+        }
+
+        let (grapheme, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(grapheme)
+    }
+}
+
+// Cells taken by one grapheme: the width of its base character.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.chars().next().map_or(0, char_width)
+}
+
+// Cells `text` takes in a terminal.
+pub fn width(text: &str) -> usize {
+    graphemes(text).map(grapheme_width).sum()
+}
+
+// The longest prefix of `text` at most `max` cells wide. A wide character
+// that would straddle the limit is left out, so the result may be one cell
+// narrower than `max`.
+pub fn truncate(text: &str, max: usize) -> &str {
+    let mut used = 0;
+    let mut end = 0;
+    for grapheme in graphemes(text) {
+        used += grapheme_width(grapheme);
+        if used > max {
+            break;
+        }
+        end += grapheme.len();
+    }
+    &text[..end]
+}
+
+// Truncates `text` to `max` cells, ending in `ellipsis` when anything was
+// cut.
+pub fn truncate_with(text: &str, max: usize, ellipsis: &str) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let room = max.saturating_sub(width(ellipsis));
+    format!("{}{}", truncate(text, room), truncate(ellipsis, max))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+// Pads `text` with spaces to `width` cells, like `{:<width$}`, `{:>width$}`
+// and `{:^width$}` but counting cells. Text already as wide is unchanged.
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = width.saturating_sub(self::width(text));
+    let (before, after) = match align {
+        Align::Left => (0, fill),
+        Align::Right => (fill, 0),
+        Align::Center => (fill / 2, fill - fill / 2),
+    };
+    format!("{}{}{}", " ".repeat(before), text, " ".repeat(after))
+}
+
+// Exactly `width` cells: truncated with an ellipsis if too wide, padded if
+// too narrow.
+pub fn fit(text: &str, width: usize, align: Align) -> String {
+    pad(&truncate_with(text, width, "…"), width, align)
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "std")]
+
+extern crate phrases;
+
+use std::fs;
+use std::path::PathBuf;
+
+use phrases::{Catalog, CatalogError, Catalogs, Locale, PhraseKey, Phrasebook};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("phrases-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn load_dir_reads_catalog_and_fluent_files_over_the_builtin_ones() {
+    let dir = scratch_dir("load-dir");
+    fs::write(dir.join("it.catalog"), "[greetings]\nhello = Ciao!\n").unwrap();
+    fs::write(dir.join("de.ftl"), "greetings-hello = Grüß Gott!\n").unwrap();
+    fs::write(dir.join("nl.txt"), "[greetings]\nhello = Hallo!\n").unwrap();
+
+    let mut catalogs = Catalogs::builtin();
+    catalogs.load_dir(&dir).unwrap();
+    let hello = |tag: &str| {
+        catalogs
+            .get(&locale(tag))
+            .and_then(|catalog| catalog.phrase(PhraseKey::Hello))
+    };
+    assert_eq!(hello("it"), Some("Ciao!".to_string()));
+    assert_eq!(hello("de"), Some("Grüß Gott!".to_string()));
+    assert_eq!(hello("nl"), None);
+    // A loaded file replaces the whole built-in catalog for its locale.
+    assert_eq!(catalogs.get(&locale("de")).unwrap().len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_dir_reports_the_first_bad_file() {
+    let dir = scratch_dir("load-dir-errors");
+    fs::write(dir.join("not a locale.catalog"), "").unwrap();
+    match Catalogs::new().load_dir(&dir) {
+        Err(CatalogError::Locale(_)) => {}
+        other => panic!("{:?}", other),
+    }
+    fs::remove_dir_all(&dir).unwrap();
+
+    match Catalogs::new().load_dir(dir.join("missing")) {
+        Err(CatalogError::Io(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn parse_errors_carry_their_line() {
+    let error = |source: &str| match Catalog::parse(locale("en"), source) {
+        Err(CatalogError::Syntax(error)) => (error.line, error.message.clone(), error.to_string()),
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(
+        error("# English\n[greetings\nhello = Hello!\n"),
+        (
+            2,
+            "malformed section header".to_string(),
+            "line 2: malformed section header".to_string()
+        )
+    );
+    assert_eq!(
+        error("[greetings]\n\nhello Hello!\n").1,
+        "expected `key = value`"
+    );
+    assert_eq!(error("[greetings]\n\n\n = Hello!\n").0, 4);
+    assert_eq!(
+        error("[greetings]\nhello = Hello!\nhello = Hi!\n"),
+        (
+            3,
+            "duplicate key `greetings.hello`".to_string(),
+            "line 3: duplicate key `greetings.hello`".to_string()
+        )
+    );
+    // The earliest problem wins, wherever the duplicate is.
+    assert_eq!(
+        error("[greetings]\nhello = Hello!\nhello = Hi!\noops\n").0,
+        3
+    );
+    assert_eq!(error("[greetings]\nhello = Hello!\n[\nhello = Hi!\n").0, 3);
+}
+
+#[test]
+fn display_writes_what_parse_reads() {
+    for catalog in Catalogs::builtin().iter() {
+        let written = catalog.to_string();
+        assert!(written.starts_with(&format!("# {}\n", catalog.locale())));
+        let reparsed = Catalog::parse(catalog.locale().clone(), &written).unwrap();
+        assert_eq!(&reparsed, catalog, "{}", written);
+        assert_eq!(reparsed.to_string(), written);
+    }
+
+    let catalog = Catalog::parse(
+        locale("en"),
+        "[farewells]\ngoodbye = Bye.\n[greetings]\nhello = Hi!\n",
+    )
+    .unwrap();
+    assert_eq!(
+        catalog.to_string(),
+        "# en\n\n[farewells]\ngoodbye = Bye.\n\n[greetings]\nhello = Hi!\n"
+    );
+}
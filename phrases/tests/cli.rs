@@ -0,0 +1,178 @@
+#![cfg(feature = "std")]
+
+use std::process::Command;
+
+// Runs the command-line tool with `args` and no locale in the environment,
+// returning its exit code, standard output and standard error.
+fn run(args: &[&str]) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_phrases"))
+        .args(args)
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG")
+        .output()
+        .unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn list_shows_locales_and_the_phrases_of_one() {
+    let (code, out, _) = run(&["list"]);
+    assert_eq!(code, 0);
+    let locales: Vec<&str> = out.lines().collect();
+    for tag in ["en", "ja", "de", "en-XA", "ar-XB"].iter() {
+        assert!(locales.contains(tag), "{:?}", locales);
+    }
+
+    let (code, out, _) = run(&["list", "--locale", "ja"]);
+    assert_eq!(code, 0);
+    assert!(out
+        .lines()
+        .any(|line| line == "greetings.hello = こんにちは"));
+}
+
+#[test]
+fn get_formats_phrases_and_warns_about_fallbacks() {
+    assert_eq!(
+        run(&["get", "hello", "--locale", "ja"]),
+        (0, "こんにちは\n".to_string(), String::new())
+    );
+    assert_eq!(
+        run(&["get", "hello_to", "--locale", "de", "--arg", "name=Welt"]).1,
+        "Hallo, Welt!\n"
+    );
+    assert_eq!(
+        run(&["get", "farewells", "--locale", "en", "--count", "1"]).1,
+        "1 farewell\n"
+    );
+    assert_eq!(
+        run(&["get", "hello", "--locale", "ja", "--register", "casual"]).1,
+        "やあ\n"
+    );
+    // Without a locale in the environment, phrases are English.
+    assert_eq!(run(&["get", "hello"]).1, "Hello!\n");
+
+    let (code, out, err) = run(&["get", "hi", "--locale", "ja"]);
+    assert_eq!((code, out.as_str()), (0, "Hi~\n"));
+    assert_eq!(
+        err,
+        "warning: `greetings.hi` has no ja translation; showing en\n"
+    );
+}
+
+#[test]
+fn json_output_names_the_serving_locale() {
+    let (code, out, err) = run(&["get", "hello", "--locale", "fr-CA", "--format", "json"]);
+    assert_eq!(code, 0);
+    assert_eq!(
+        out,
+        "{\"locale\":\"fr-CA\",\"served_by\":\"fr\",\"direction\":\"ltr\",\"text\":\"Bonjour !\",\"key\":\"greetings.hello\"}\n"
+    );
+    // The fallback is in the output, so it is not warned about.
+    assert_eq!(err, "");
+
+    let (code, out, _) = run(&[
+        "get",
+        "hello",
+        "--accept-language",
+        "de;q=0.5, ja",
+        "--format",
+        "json",
+    ]);
+    assert_eq!(code, 0);
+    assert!(out.contains("\"text\":\"こんにちは\""), "{}", out);
+    assert!(out.contains("\"quality\":1"), "{}", out);
+}
+
+#[test]
+fn translate_shows_both_locales() {
+    assert_eq!(
+        run(&["translate", "goodbye", "--from", "en", "--to", "ja"]),
+        (
+            0,
+            "en: Goodbye.\nja: さようなら\n".to_string(),
+            String::new()
+        )
+    );
+    let (code, out, _) = run(&[
+        "translate",
+        "goodbye",
+        "--from",
+        "en",
+        "--to",
+        "de",
+        "--format",
+        "json",
+    ]);
+    assert_eq!(code, 0);
+    assert!(
+        out.starts_with("{\"key\":\"farewells.goodbye\",\"from\":{"),
+        "{}",
+        out
+    );
+    assert!(out.contains("\"text\":\"Auf Wiedersehen.\""), "{}", out);
+
+    let (code, _, err) = run(&["translate", "goodbye"]);
+    assert_eq!(code, 1);
+    assert_eq!(err, "`translate` needs --to\n");
+}
+
+#[test]
+fn help_prints_the_usage_and_succeeds() {
+    for flag in ["--help", "-h"].iter() {
+        let (code, out, err) = run(&[flag]);
+        assert_eq!(code, 0);
+        assert!(out.starts_with("usage: phrases <command>"), "{}", out);
+        assert_eq!(err, "");
+    }
+    assert_eq!(run(&["get", "hello", "--help"]).0, 0);
+}
+
+#[test]
+fn mistakes_on_the_command_line_exit_with_two_and_the_usage() {
+    for args in [
+        &["get", "hello", "--bogus", "x"][..],
+        &["get", "hello", "--locale"],
+        &["frobnicate"],
+        &[],
+        &["get", "farewells", "--count", "2", "--register", "casual"],
+    ]
+    .iter()
+    {
+        let (code, out, err) = run(args);
+        assert_eq!(code, 2, "{:?}", args);
+        assert_eq!(out, "");
+        assert!(err.contains("usage: phrases"), "{:?}: {}", args, err);
+    }
+    assert!(
+        run(&["get", "farewells", "--count", "2", "--register", "casual"])
+            .2
+            .starts_with("--register cannot be combined with --count\n")
+    );
+}
+
+#[test]
+fn failures_exit_with_one_and_no_usage() {
+    for args in [
+        &["get", "nope"][..],
+        &["get"],
+        &["get", "hello_to", "--locale", "en"],
+        &["get", "hello", "--catalogs", "/nonexistent/phrases"],
+    ]
+    .iter()
+    {
+        let (code, out, err) = run(args);
+        assert_eq!(code, 1, "{:?}", args);
+        assert_eq!(out, "");
+        assert!(
+            !err.is_empty() && !err.contains("usage:"),
+            "{:?}: {}",
+            args,
+            err
+        );
+    }
+}
@@ -0,0 +1,115 @@
+extern crate phrases;
+
+use phrases::conventions;
+use phrases::{message, Args, Currency, Date, DateStyle, Locale, PhraseKey, Resolver, Value};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn currency(code: &str) -> Currency {
+    code.parse().unwrap()
+}
+
+#[test]
+fn numbers_follow_each_locale() {
+    let number = |tag: &str, value: f64| conventions::of(&locale(tag)).number(value);
+    assert_eq!(number("en", 1234.5), "1,234.5");
+    assert_eq!(number("de", 1234.5), "1.234,5");
+    assert_eq!(number("fr-FR", 1234.5), "1\u{202F}234,5");
+    assert_eq!(number("ja", 1234.5), "1,234.5");
+    assert_eq!(number("en", -1234567.8912), "-1,234,567.891");
+    assert_eq!(number("en", 0.1 + 0.2), "0.3");
+    assert_eq!(number("en", -0.0001), "0");
+
+    // Spanish leaves four-digit numbers ungrouped.
+    assert_eq!(number("es", 1234.0), "1234");
+    assert_eq!(number("es", 12345.0), "12.345");
+
+    // Languages without data of their own get the locale-neutral form.
+    assert_eq!(number("ko", 1234.5), "1234.5");
+}
+
+#[test]
+fn percentages_and_amounts_follow_each_locale() {
+    let en = conventions::of(&locale("en"));
+    let de = conventions::of(&locale("de"));
+    let ja = conventions::of(&locale("ja"));
+    assert_eq!(en.percent(0.256), "26%");
+    assert_eq!(de.percent(0.256), "26\u{A0}%");
+
+    assert_eq!(en.currency(1234.5, &currency("usd")), "$1,234.50");
+    assert_eq!(de.currency(1234.5, &currency("EUR")), "1.234,50\u{A0}€");
+    assert_eq!(ja.currency(1234.0, &currency("JPY")), "￥1,234");
+    assert_eq!(en.currency(-5.0, &currency("GBP")), "-£5.00");
+    assert_eq!(en.currency(12.0, &currency("CHF")), "CHF\u{A0}12.00");
+    assert!("EURO".parse::<Currency>().is_err());
+}
+
+#[test]
+fn dates_follow_each_locale() {
+    let date = Date::new(2026, 1, 31).unwrap();
+    let format = |tag: &str, style| conventions::of(&locale(tag)).date(date, style);
+    assert_eq!(format("en", DateStyle::Short), "1/31/2026");
+    assert_eq!(format("en", DateStyle::Long), "January 31, 2026");
+    assert_eq!(format("de", DateStyle::Long), "31. Januar 2026");
+    assert_eq!(format("es", DateStyle::Long), "31 de enero de 2026");
+    assert_eq!(format("fr", DateStyle::Short), "31/01/2026");
+    assert_eq!(format("ja", DateStyle::Long), "2026年1月31日");
+    assert_eq!(format("zh", DateStyle::Short), "2026/1/31");
+
+    assert_eq!(Date::new(2024, 2, 29).map(|date| date.day()), Some(29));
+    assert_eq!(Date::new(2025, 2, 29), None);
+    assert!("2026-13-01".parse::<Date>().is_err());
+}
+
+#[test]
+fn typed_arguments_are_written_for_the_serving_locale() {
+    let args = Args::new().with_value("name", Value::Number(1234.5));
+    let resolver = Resolver::new();
+    assert_eq!(
+        resolver
+            .format(&locale("de-AT"), PhraseKey::HelloTo, &args)
+            .unwrap()
+            .text,
+        "Hallo, 1.234,5!"
+    );
+    assert_eq!(
+        phrases::format(&locale("fr"), PhraseKey::HelloTo, &args).unwrap(),
+        "Bonjour, 1\u{202F}234,5 !"
+    );
+
+    // Counts are numbers too.
+    let text = phrases::format_count(&locale("de"), PhraseKey::Farewells, 1500, &Args::new());
+    assert_eq!(text.unwrap(), "1.500 Abschiede");
+
+    // Without a locale, values take their neutral forms.
+    let args = Args::new().with_value(
+        "name",
+        Value::Date(Date::new(2026, 1, 31).unwrap(), DateStyle::Long),
+    );
+    assert_eq!(
+        message::format("Hello, {name}!", &args).unwrap(),
+        "Hello, 2026-01-31!"
+    );
+}
+
+#[test]
+fn arguments_parse_from_typed_names() {
+    let mut args = Args::new();
+    args.set_parsed("price:currency", "9.5 EUR").unwrap();
+    args.set_parsed("on:long-date", "2026-01-31").unwrap();
+    args.set_parsed("name", "Ada").unwrap();
+    assert_eq!(
+        args.get("price"),
+        Some(&Value::Currency(9.5, currency("EUR")))
+    );
+    assert_eq!(args.get("name"), Some(&Value::Text("Ada".to_string())));
+    assert_eq!(
+        message::format_localized("{name}: {price}, {on}", &args, &locale("en")).unwrap(),
+        "Ada: €9.50, January 31, 2026"
+    );
+
+    assert!(args.set_parsed("n:number", "many").is_err());
+    assert!(args.set_parsed("n:money", "1").is_err());
+}
@@ -0,0 +1,98 @@
+extern crate phrases;
+
+use phrases::coverage::{self, PlaceholderMismatch};
+use phrases::{Catalog, Catalogs, Locale};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn english() -> Catalog {
+    Catalog::parse(
+        locale("en"),
+        "[greetings]\nhello = Hello!\nhello@casual = Hey!\nhello_to = Hello, {name}!\n\n[farewells.farewells]\none = {count} farewell\nother = {count} farewells\n",
+    )
+    .unwrap()
+}
+
+#[test]
+fn a_full_translation_is_complete() {
+    // Registers are optional, and Japanese has a single plural form.
+    let japanese = Catalog::parse(
+        locale("ja"),
+        "[greetings]\nhello = こんにちは\nhello_to = こんにちは、{name}さん\n\n[farewells.farewells]\nother = {count}回のさようなら\n",
+    )
+    .unwrap();
+    let coverage = coverage::compare(&english(), &japanese);
+    assert!(coverage.is_complete(), "{}", coverage);
+}
+
+#[test]
+fn reports_missing_and_extra_entries() {
+    let french = Catalog::parse(
+        locale("fr"),
+        "[greetings]\nhello = Bonjour !\nhowdy = Salut !\nhowdy@casual = Coucou !\n\n[farewells.farewells]\none = {count} adieu\n",
+    )
+    .unwrap();
+    let coverage = coverage::compare(&english(), &french);
+    assert!(!coverage.is_complete());
+    assert_eq!(
+        coverage.missing,
+        ["farewells.farewells.other", "greetings.hello_to"]
+    );
+    assert_eq!(
+        coverage.extra,
+        ["greetings.howdy", "greetings.howdy@casual"]
+    );
+    assert_eq!(coverage.mismatched, []);
+}
+
+#[test]
+fn reports_mismatched_placeholders_in_every_form() {
+    let german = Catalog::parse(
+        locale("de"),
+        "[greetings]\nhello = Hallo!\nhello@casual = Hi, {name}!\nhello_to = Hallo, {nom}!\n\n[farewells.farewells]\none = ein Abschied\nother = {count} Abschiede\n",
+    )
+    .unwrap();
+    let coverage = coverage::compare(&english(), &german);
+    assert_eq!(coverage.missing, Vec::<String>::new());
+    assert_eq!(
+        coverage.mismatched,
+        [
+            PlaceholderMismatch {
+                key: "farewells.farewells.one".to_string(),
+                expected: vec!["count".to_string()],
+                found: vec![],
+            },
+            PlaceholderMismatch {
+                key: "greetings.hello@casual".to_string(),
+                expected: vec![],
+                found: vec!["name".to_string()],
+            },
+            PlaceholderMismatch {
+                key: "greetings.hello_to".to_string(),
+                expected: vec!["name".to_string()],
+                found: vec!["nom".to_string()],
+            },
+        ]
+    );
+    assert_eq!(
+        coverage.to_string().lines().next(),
+        Some("de (reference en): 0 missing, 0 extra, 3 placeholder mismatches")
+    );
+}
+
+#[test]
+fn reports_leave_out_the_reference_and_pseudo_locales() {
+    let catalogs = Catalogs::builtin();
+    let report = coverage::report(&catalogs, &locale("en")).unwrap();
+    let locales: Vec<String> = report
+        .iter()
+        .map(|coverage| coverage.locale.to_string())
+        .collect();
+    assert!(locales.contains(&"ja".to_string()), "{:?}", locales);
+    for excluded in ["en", "en-XA", "ar-XB"].iter() {
+        assert!(!locales.contains(&excluded.to_string()), "{:?}", locales);
+    }
+    assert!(coverage::report(&catalogs, &locale("zz")).is_none());
+}
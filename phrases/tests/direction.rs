@@ -0,0 +1,117 @@
+extern crate phrases;
+
+use phrases::direction::{self, FIRST_STRONG_ISOLATE as FSI, POP_DIRECTIONAL_ISOLATE as PDI};
+use phrases::fluent::Bundle;
+#[cfg(feature = "std")]
+use phrases::PhraseRegistry;
+use phrases::{message, Args, Direction, Locale, PhraseKey, Resolver};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn isolated(text: &str) -> String {
+    format!("{}{}{}", FSI, text, PDI)
+}
+
+#[test]
+fn locales_know_their_direction() {
+    assert_eq!(direction::of(&locale("ar")), Direction::RightToLeft);
+    assert_eq!(direction::of(&locale("he-IL")), Direction::RightToLeft);
+    assert_eq!(direction::of(&locale("fa")), Direction::RightToLeft);
+    assert_eq!(direction::of(&locale("ar-XB")), Direction::RightToLeft);
+    assert_eq!(direction::of(&locale("en")), Direction::LeftToRight);
+    assert_eq!(direction::of(&locale("ja-JP")), Direction::LeftToRight);
+    assert_eq!(Direction::RightToLeft.to_string(), "rtl");
+}
+
+#[test]
+fn text_direction_follows_the_first_strong_character() {
+    assert_eq!(direction::of_text("Salma"), Some(Direction::LeftToRight));
+    assert_eq!(direction::of_text("سلمى"), Some(Direction::RightToLeft));
+    assert_eq!(direction::of_text("42 שלום"), Some(Direction::RightToLeft));
+    assert_eq!(direction::of_text("42!"), None);
+}
+
+#[test]
+fn latin_argument_in_right_to_left_text_is_isolated() {
+    let text = message::format_directional(
+        "مرحبا، {name}!",
+        &Args::new().with("name", "John Smith"),
+        Direction::RightToLeft,
+    )
+    .unwrap();
+    assert_eq!(text, format!("مرحبا، {}!", isolated("John Smith")));
+}
+
+#[test]
+fn right_to_left_argument_in_latin_text_is_isolated() {
+    let text = message::format_directional(
+        "Hello, {name}!",
+        &Args::new().with("name", "שרה"),
+        Direction::LeftToRight,
+    )
+    .unwrap();
+    assert_eq!(text, format!("Hello, {}!", isolated("שרה")));
+}
+
+#[test]
+fn latin_argument_in_latin_text_is_left_alone() {
+    let text = message::format_directional(
+        "Hello, {name}!",
+        &Args::new().with("name", "Sam"),
+        Direction::LeftToRight,
+    )
+    .unwrap();
+    assert_eq!(text, "Hello, Sam!");
+}
+
+#[test]
+fn plain_format_never_isolates() {
+    let args = Args::new().with("name", "سلمى");
+    assert_eq!(message::format("Hi {name}", &args).unwrap(), "Hi سلمى");
+}
+
+#[test]
+fn resolver_isolates_by_serving_locale() {
+    let resolver = Resolver::new();
+    let args = Args::new().with("name", "Bob");
+
+    let english = resolver
+        .format(&locale("en"), PhraseKey::HelloTo, &args)
+        .unwrap();
+    assert_eq!(english.text, "Hello, Bob!");
+
+    let pseudo = resolver
+        .format(&locale("ar-XB"), PhraseKey::HelloTo, &args)
+        .unwrap();
+    assert!(pseudo.text.contains(&isolated("Bob")));
+
+    let count = resolver
+        .format_count(&locale("ar-XB"), PhraseKey::Farewells, 3, &Args::new())
+        .unwrap();
+    assert!(count.text.contains(&isolated("3")));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn registry_isolates_arguments() {
+    let snapshot = PhraseRegistry::builtin().snapshot();
+    let text = snapshot
+        .format(
+            &locale("ar-XB"),
+            PhraseKey::HelloTo,
+            &Args::new().with("name", "Bob"),
+        )
+        .unwrap();
+    assert!(text.contains(&isolated("Bob")));
+}
+
+#[test]
+fn fluent_isolates_arguments() {
+    let bundle = Bundle::parse(locale("he"), "hello = שלום, { $name }!\n").unwrap();
+    let text = bundle
+        .format("hello", &Args::new().with("name", "Dana"))
+        .unwrap();
+    assert_eq!(text, format!("שלום, {}!", isolated("Dana")));
+}
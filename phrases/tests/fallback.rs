@@ -0,0 +1,86 @@
+extern crate phrases;
+
+use phrases::{Catalog, Catalogs, Locale, PhraseKey, Resolver};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn tags(chain: Vec<Locale>) -> Vec<String> {
+    chain.iter().map(|locale| locale.to_string()).collect()
+}
+
+#[test]
+fn chains_run_from_the_tag_through_its_language_to_the_default() {
+    let resolver = Resolver::new();
+    assert_eq!(
+        tags(resolver.chain(&locale("pt-BR"))),
+        ["pt-BR", "pt", "en"]
+    );
+    assert_eq!(tags(resolver.chain(&locale("ja"))), ["ja", "en"]);
+    // The default is not repeated.
+    assert_eq!(tags(resolver.chain(&locale("en-GB"))), ["en-GB", "en"]);
+    assert_eq!(tags(resolver.chain(&locale("en"))), ["en"]);
+
+    let french = Resolver::with_default(locale("fr"));
+    assert_eq!(tags(french.chain(&locale("pt-BR"))), ["pt-BR", "pt", "fr"]);
+    assert_eq!(french.default_locale(), &locale("fr"));
+}
+
+#[test]
+fn each_key_is_served_by_the_first_locale_in_the_chain_that_has_it() {
+    let mut catalogs = Catalogs::builtin();
+    catalogs.insert(
+        Catalog::parse(
+            locale("pt"),
+            "[greetings]\nhello = Olá!\n\n[farewells]\ngoodbye = Adeus.\n",
+        )
+        .unwrap(),
+    );
+    catalogs.insert(Catalog::parse(locale("pt-BR"), "[farewells]\ngoodbye = Tchau.\n").unwrap());
+    let resolver = Resolver::new().with_catalogs(catalogs);
+
+    let served = |key: PhraseKey| {
+        let resolved = resolver.resolve(&locale("pt-BR"), key).unwrap();
+        assert_eq!(resolved.requested, locale("pt-BR"));
+        let fallback = resolved.is_fallback();
+        (resolved.text, resolved.locale.to_string(), fallback)
+    };
+    assert_eq!(
+        served(PhraseKey::Goodbye),
+        ("Tchau.".to_string(), "pt-BR".to_string(), false)
+    );
+    assert_eq!(
+        served(PhraseKey::Hello),
+        ("Olá!".to_string(), "pt".to_string(), false)
+    );
+    // Missing in Portuguese altogether, so English.
+    assert_eq!(
+        served(PhraseKey::GoodEvening),
+        ("Good evening!".to_string(), "en".to_string(), true)
+    );
+}
+
+#[test]
+fn missing_keys_fall_back_to_english() {
+    let resolver = Resolver::new();
+    let resolved = resolver.resolve(&locale("ja-JP"), PhraseKey::Hi).unwrap();
+    assert_eq!(resolved.text, "Hi~");
+    assert_eq!(resolved.locale, locale("en"));
+    assert!(resolved.is_fallback());
+
+    let resolved = resolver.resolve(&locale("ko"), PhraseKey::Hello).unwrap();
+    assert_eq!(resolved.text, "Hello!");
+    assert!(resolved.is_fallback());
+
+    // A regional English is served by `en` without counting as a fallback.
+    let resolved = resolver
+        .resolve(&locale("en-AU"), PhraseKey::Hello)
+        .unwrap();
+    assert_eq!(resolved.locale, locale("en"));
+    assert!(!resolved.is_fallback());
+
+    // With nothing at the end of the chain, there is no phrase.
+    let nowhere = Resolver::with_default(locale("xx"));
+    assert_eq!(nowhere.resolve(&locale("yy-ZZ"), PhraseKey::Hello), None);
+}
@@ -0,0 +1,100 @@
+extern crate phrases;
+
+use phrases::fluent::{self, Bundle, Position};
+use phrases::{Args, Locale};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+// The line, column and message of every error in `source`.
+fn errors(source: &str) -> Vec<(usize, usize, String)> {
+    fluent::parse(source)
+        .1
+        .into_iter()
+        .map(|diagnostic| {
+            let Position { line, column } = diagnostic.position;
+            (line, column, diagnostic.message)
+        })
+        .collect()
+}
+
+#[test]
+fn unclosed_placeables_are_reported_at_their_opening_brace() {
+    let source = "\
+hello = Hello!
+bad = { $x
+next = Still parsed
+";
+    assert_eq!(errors(source), [(2, 7, "unclosed placeable".to_string())]);
+    assert_eq!(fluent::parse(source).0.len(), 2);
+
+    assert_eq!(
+        errors("greetings-hello = {\n"),
+        [(1, 19, "unclosed placeable".to_string())]
+    );
+    assert_eq!(
+        errors("count = { $n ->\n    *[other] many\n"),
+        [(1, 9, "unclosed placeable".to_string())]
+    );
+    // On its own line, a stray token is reported where it stands.
+    assert_eq!(
+        errors("bad = { $x $y }\n"),
+        [(1, 12, "expected `}`".to_string())]
+    );
+}
+
+#[test]
+fn syntax_errors_carry_line_and_column() {
+    let source = "\
+# A comment
+ok = Fine
+closing = oops }
+select = { $n ->
+    [one] one
+ }
+";
+    assert_eq!(
+        errors(source),
+        [
+            (3, 16, "unbalanced `}`".to_string()),
+            (
+                4,
+                17,
+                "select expression needs a default `*[...]` variant".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn deeply_nested_placeables_are_refused() {
+    let depth = 200_000;
+    let source = format!("deep = {}\"x\"{}\n", "{".repeat(depth), "}".repeat(depth));
+    let errors = errors(&source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].2, "placeables nested too deeply");
+
+    let source = format!("nested = {}\"x\"{}\n", "{".repeat(10), "}".repeat(10));
+    let bundle = Bundle::parse(locale("en"), &source).unwrap();
+    assert_eq!(bundle.format("nested", &Args::new()).unwrap(), "x");
+}
+
+#[test]
+fn unresolved_references_are_located() {
+    let diagnostics = Bundle::parse(locale("en"), "a = Hi\nb = { a } and { missing }\n")
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].position,
+        Position {
+            line: 2,
+            column: 15
+        }
+    );
+    assert_eq!(
+        diagnostics[0].to_string(),
+        format!("2:15: {}", diagnostics[0].message)
+    );
+}
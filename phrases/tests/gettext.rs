@@ -0,0 +1,111 @@
+extern crate phrases;
+
+use phrases::gettext::{self, GettextError};
+use phrases::{Catalog, Catalogs, Locale};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn catalogs() -> (Catalog, Catalog) {
+    let catalogs = Catalogs::builtin();
+    (
+        catalogs.get(&locale("en")).unwrap().clone(),
+        catalogs.get(&locale("de")).unwrap().clone(),
+    )
+}
+
+fn mo_error(bytes: &[u8]) -> String {
+    match gettext::from_mo(bytes) {
+        Err(GettextError::Mo(message)) => message,
+        other => panic!("expected a .mo error, got {:?}", other),
+    }
+}
+
+// A `.mo` header: magic, revision, count and the two table offsets.
+fn header(count: u32, originals: u32, translations: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for word in &[0x9504_12de, 0, count, originals, translations, 0, 28] {
+        bytes.extend_from_slice(&u32::to_le_bytes(*word));
+    }
+    bytes
+}
+
+#[test]
+fn catalogs_round_trip_through_po_and_mo() {
+    let (reference, german) = catalogs();
+    let po = gettext::to_po(&reference, &german);
+    assert!(po.contains("msgctxt \"farewells.farewells\""));
+    assert_eq!(gettext::from_po(&po).unwrap(), german);
+
+    let mo = gettext::to_mo(&reference, &german);
+    assert_eq!(gettext::from_mo(&mo).unwrap(), german);
+
+    // Big-endian files read the same.
+    let mut swapped = mo.clone();
+    for word in swapped[..28].chunks_mut(4) {
+        word.reverse();
+    }
+    let count = u32::from_le_bytes([mo[8], mo[9], mo[10], mo[11]]) as usize;
+    for word in swapped[28..28 + 16 * count].chunks_mut(4) {
+        word.reverse();
+    }
+    assert_eq!(gettext::from_mo(&swapped).unwrap(), german);
+}
+
+#[test]
+fn malformed_mo_files_are_errors() {
+    let (reference, german) = catalogs();
+    let mo = gettext::to_mo(&reference, &german);
+
+    assert_eq!(mo_error(&mo[..14]), "truncated");
+    assert_eq!(mo_error(&mo[..mo.len() - 10]), "string out of bounds");
+    assert_eq!(mo_error(&[0; 28]), "bad magic number");
+    assert_eq!(mo_error(&header(1, 28, 4096)), "string table out of bounds");
+
+    let mut bytes = header(1, 28, 36);
+    for word in &[4, 0xFFFF_FFF0, 0, 44] {
+        bytes.extend_from_slice(&u32::to_le_bytes(*word));
+    }
+    assert_eq!(mo_error(&bytes), "string out of bounds");
+}
+
+#[test]
+fn huge_string_counts_are_refused_before_allocating() {
+    assert_eq!(
+        mo_error(&header(0xFFFF_FFFF, 28, 28)),
+        "string table out of bounds"
+    );
+    assert_eq!(
+        mo_error(&header(0x2000_0000, 0xFFFF_FFFF, 28)),
+        "string table out of bounds"
+    );
+}
+
+#[test]
+fn huge_plural_indices_are_refused_before_allocating() {
+    let source = "msgctxt \"farewells.farewells\"\nmsgid \"{count} farewell\"\nmsgid_plural \"{count} farewells\"\nmsgstr[4000000000] \"x\"\n";
+    match gettext::parse_po(source) {
+        Err(GettextError::Syntax { line, message }) => {
+            assert_eq!(line, 4);
+            assert_eq!(message, "plural index 4000000000 out of range");
+        }
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    assert!(gettext::parse_po(&source.replace("4000000000", "6")).is_err());
+    assert_eq!(
+        gettext::parse_po(&source.replace("4000000000", "5")).unwrap()[0]
+            .translations
+            .len(),
+        6
+    );
+}
+
+#[test]
+fn malformed_po_files_are_errors() {
+    match gettext::from_po("msgid \"\"\nmsgstr \"\"\n") {
+        Err(GettextError::MissingLanguage) => {}
+        other => panic!("expected a missing language, got {:?}", other),
+    }
+    assert!(gettext::from_po("msgid \"unterminated\n").is_err());
+}
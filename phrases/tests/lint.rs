@@ -0,0 +1,128 @@
+extern crate phrases;
+
+use phrases::lint::{self, Check, Severity};
+use phrases::{Catalogs, Locale};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+// The checks reported for `source`, as (line, check) pairs in line order.
+fn checks(tag: &str, source: &[u8]) -> Vec<(usize, Check)> {
+    let catalogs = Catalogs::builtin();
+    lint::lint(&locale(tag), source, catalogs.get(&locale("en")))
+        .iter()
+        .map(|diagnostic| (diagnostic.line, diagnostic.check))
+        .collect()
+}
+
+#[test]
+fn clean_catalogs_have_no_diagnostics() {
+    let source = "# German\n[greetings]\nhello = Hallo!\nhello_to = Hallo, {name}!\n\n[farewells]\ngoodbye = Auf Wiedersehen.\n";
+    assert_eq!(checks("de", source.as_bytes()), []);
+}
+
+#[test]
+fn reports_every_problem_with_its_line() {
+    let source = "[greetings]\nhello = Hallo! \nhello_to = Hallo, {nom}!\nhello = Hi!\nwelcome = Welcome, {name}!\n\n[farewells]\ngoodbye = Tschüss\ngoodbye_to = Tschüss, {name.\n";
+    assert_eq!(
+        checks("de", source.as_bytes()),
+        [
+            (2, Check::Whitespace),
+            (3, Check::Placeholders),
+            (4, Check::DuplicateKey),
+            (5, Check::Untranslated),
+            (8, Check::Punctuation),
+            (9, Check::Placeholders),
+        ]
+    );
+}
+
+#[test]
+fn punctuation_compares_full_width_marks_and_select_variants() {
+    let source = "[greetings]\nhello = こんにちは！\ngood_morning = おはよう\nwelcome = {gender, select, female {Bienvenida, {name}!} other {Hola, {name}!}}\n";
+    let diagnostics = lint::lint(
+        &locale("ja"),
+        source.as_bytes(),
+        Catalogs::builtin().get(&locale("en")),
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 3);
+    assert_eq!(diagnostics[0].check, Check::Punctuation);
+    assert_eq!(diagnostics[0].severity(), Severity::Warning);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "3: warning: `greetings.good_morning` does not end with `!` as en does [punctuation]"
+    );
+}
+
+#[test]
+fn invalid_utf8_is_an_error_and_the_rest_is_still_linted() {
+    let source = b"[greetings]\nhello = Hal\xFFlo!\nhello_to = Hallo, {nom}!\n";
+    let diagnostics = lint::lint(&locale("de"), source, None);
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.check, diagnostic.severity()))
+            .collect::<Vec<_>>(),
+        [(2, Check::Encoding, Severity::Error)]
+    );
+    assert_eq!(diagnostics[0].message, "invalid UTF-8 (bytes FF)");
+
+    // Without a reference only the file itself is checked; with one, the
+    // placeholder mismatch on the next line shows up too.
+    assert_eq!(
+        checks("de", source),
+        [(2, Check::Encoding), (3, Check::Placeholders)]
+    );
+}
+
+#[test]
+fn the_reference_and_same_language_catalogs_are_not_untranslated() {
+    let source = "[greetings]\nhello = Hello!\nhello_to = Hello, {name}!\n";
+    assert_eq!(checks("en", source.as_bytes()), []);
+    assert_eq!(checks("en-GB", source.as_bytes()), []);
+    assert_eq!(
+        checks("fr", source.as_bytes()),
+        [(2, Check::Untranslated), (3, Check::Untranslated)]
+    );
+}
+
+#[test]
+fn fluent_files_are_linted_as_the_entries_they_become() {
+    let source = "greetings-hello = Hallo\ngreetings-hello_to = Hallo, { $nom }!   \n";
+    let catalogs = Catalogs::builtin();
+    let diagnostics = lint::lint_fluent(
+        &locale("de"),
+        source.as_bytes(),
+        catalogs.get(&locale("en")),
+    );
+    let checks: Vec<(usize, Check)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.line, diagnostic.check))
+        .collect();
+    assert_eq!(
+        checks,
+        [
+            (1, Check::Punctuation),
+            (2, Check::Whitespace),
+            (2, Check::Placeholders),
+        ]
+    );
+
+    // Plural forms and registers are reported at the message they belong to.
+    let source = "greetings-hello = Hallo!\n    .casual = Hi, { $name }!\n\nfarewells-farewells = { $count ->\n    [one] ein Abschied\n   *[other] { $count } Abschiede\n}\n";
+    let lines: Vec<(usize, Check)> = lint::lint_fluent(
+        &locale("de"),
+        source.as_bytes(),
+        catalogs.get(&locale("en")),
+    )
+    .iter()
+    .map(|diagnostic| (diagnostic.line, diagnostic.check))
+    .collect();
+    assert_eq!(lines, [(1, Check::Placeholders), (4, Check::Placeholders)]);
+
+    let broken = lint::lint_fluent(&locale("de"), b"greetings-hello = {\n", None);
+    assert_eq!(broken[0].check, Check::Syntax);
+    assert_eq!(broken[0].line, 1);
+}
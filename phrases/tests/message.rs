@@ -0,0 +1,86 @@
+extern crate phrases;
+
+use phrases::message;
+use phrases::{Args, FormatError, Locale, PhraseKey};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+#[test]
+fn fills_in_placeholders_and_escaped_braces() {
+    let args = Args::new().with("name", "Sam").with("count", 3);
+    assert_eq!(
+        message::format("{{{name}}} has {count} {{items}}", &args).unwrap(),
+        "{Sam} has 3 {items}"
+    );
+    assert_eq!(message::placeholders("{b} {a} {b}").unwrap(), ["b", "a"]);
+}
+
+#[test]
+fn a_placeholder_without_an_argument_is_missing() {
+    assert_eq!(
+        message::format("Hello, {name}!", &Args::new()),
+        Err(FormatError::MissingArgument("name".to_string()))
+    );
+    assert_eq!(
+        phrases::format(&locale("en"), PhraseKey::HelloTo, &Args::new()),
+        Err(FormatError::MissingArgument("name".to_string()))
+    );
+    let err = message::format("{a}{b}", &Args::new().with("a", 1)).unwrap_err();
+    assert_eq!(err, FormatError::MissingArgument("b".to_string()));
+    assert_eq!(err.to_string(), "missing argument `b`");
+}
+
+#[test]
+fn an_argument_no_placeholder_uses_is_unexpected() {
+    let args = Args::new().with("name", "Sam").with("nmae", "Sam");
+    assert_eq!(
+        message::format("Hello, {name}!", &args),
+        Err(FormatError::UnexpectedArgument("nmae".to_string()))
+    );
+    let err = phrases::format(
+        &locale("de"),
+        PhraseKey::Hello,
+        &Args::new().with("name", "Welt"),
+    )
+    .unwrap_err();
+    assert_eq!(err, FormatError::UnexpectedArgument("name".to_string()));
+    assert_eq!(err.to_string(), "unexpected argument `name`");
+
+    // Selectors count as uses, attributes are never arguments.
+    let template = "{gender, select, other {Hi}}";
+    assert!(message::format(template, &Args::new().with("gender", "x")).is_ok());
+    assert!(message::format("Hi", &Args::new().with_attribute("gender", "x")).is_ok());
+}
+
+#[test]
+fn malformed_templates_are_syntax_errors_at_their_offset() {
+    let syntax = |template: &str| match message::format(template, &Args::new()) {
+        Err(FormatError::Syntax { offset, message }) => (offset, message),
+        other => panic!("expected a syntax error, got {:?}", other),
+    };
+    assert_eq!(syntax("Hello, {name"), (7, "unclosed `{`".to_string()));
+    assert_eq!(syntax("Hello }"), (6, "unmatched `}`".to_string()));
+    assert_eq!(
+        syntax("Hello, {}!"),
+        (7, "expected a placeholder name".to_string())
+    );
+    assert_eq!(
+        message::placeholders("{name").unwrap_err().to_string(),
+        "invalid template at byte 0: unclosed `{`"
+    );
+}
+
+#[test]
+fn a_phrase_no_locale_has_is_missing() {
+    let resolver = phrases::Resolver::with_default(locale("xx"));
+    assert_eq!(
+        resolver.format(&locale("yy"), PhraseKey::Hello, &Args::new()),
+        Err(FormatError::MissingPhrase("greetings.hello".to_string()))
+    );
+    assert_eq!(
+        phrases::format(&locale("zz"), PhraseKey::Hello, &Args::new()),
+        Err(FormatError::MissingPhrase("greetings.hello".to_string()))
+    );
+}
@@ -0,0 +1,115 @@
+extern crate phrases;
+
+use phrases::negotiate::{self, Preference};
+use phrases::{Locale, PhraseKey, Resolver};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn locales(tags: &[&str]) -> Vec<Locale> {
+    tags.iter().map(|tag| locale(tag)).collect()
+}
+
+// The locale `header` negotiates to among `available`, defaulting to `en`.
+fn negotiated(header: &str, available: &[&str]) -> Option<String> {
+    negotiate::negotiate(
+        &negotiate::parse_accept_language(header),
+        &locales(available),
+        &locale("en"),
+    )
+    .map(|negotiated| negotiated.locale.to_string())
+}
+
+#[test]
+fn preferences_parse_in_quality_order() {
+    let preferences = negotiate::parse_accept_language("fr;q=0.5, ja, *;q=0.1, de;q=2, en;q=x");
+    assert_eq!(
+        preferences,
+        [
+            Preference {
+                locale: Some(locale("ja")),
+                quality: 1.0,
+            },
+            Preference {
+                locale: Some(locale("fr")),
+                quality: 0.5,
+            },
+            Preference {
+                locale: None,
+                quality: 0.1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn regions_fall_back_to_their_language_and_back() {
+    let available = ["en", "ja", "fr-CA"];
+    assert_eq!(negotiated("ja-JP", &available), Some("ja".to_string()));
+    assert_eq!(negotiated("fr", &available), Some("fr-CA".to_string()));
+    assert_eq!(
+        negotiated("zh-Hant-TW, ja;q=0.3", &available),
+        Some("ja".to_string())
+    );
+    assert_eq!(negotiated("ko", &available), None);
+
+    // The exact locale wins over its language.
+    assert_eq!(
+        negotiated("fr-CA", &["fr", "fr-CA"]),
+        Some("fr-CA".to_string())
+    );
+}
+
+#[test]
+fn zero_quality_refuses_and_the_wildcard_means_the_default() {
+    let available = ["en", "ja", "de"];
+    assert_eq!(negotiated("ja;q=0, *", &available), Some("en".to_string()));
+    assert_eq!(negotiated("en;q=0, ja;q=0, *", &available), None);
+    assert_eq!(negotiated("de;q=0, de-AT", &available), None);
+    assert_eq!(negotiated("*", &["ja"]), None);
+
+    let found = negotiate::negotiate(
+        &negotiate::parse_accept_language("ko, de;q=0.4"),
+        &locales(&available),
+        &locale("en"),
+    )
+    .unwrap();
+    assert_eq!(found.quality, 0.4);
+}
+
+#[test]
+fn pseudo_locales_are_served_only_by_name() {
+    let available = ["en", "ja", "en-XA", "ar-XB"];
+    assert_eq!(
+        negotiated("ar, en;q=0.5", &available),
+        Some("en".to_string())
+    );
+    assert_eq!(negotiated("ar-SA", &available), None);
+    assert_eq!(negotiated("en-US", &["en-XA", "ja"]), None);
+    assert_eq!(negotiated("ar-XB", &available), Some("ar-XB".to_string()));
+    assert_eq!(
+        negotiated("en-XA, en", &available),
+        Some("en-XA".to_string())
+    );
+
+    // The built-in locales include the pseudo-locales.
+    let resolver = Resolver::new();
+    let found = resolver.negotiate("ar, en;q=0.5").unwrap();
+    assert_eq!(found.locale, locale("en"));
+    let resolved = resolver
+        .resolve(
+            &negotiate::parse_posix("ar_SA.UTF-8").unwrap(),
+            PhraseKey::Hello,
+        )
+        .unwrap();
+    assert_eq!(resolved.locale, locale("en"));
+}
+
+#[test]
+fn posix_locales_parse_to_tags() {
+    assert_eq!(negotiate::parse_posix("ja_JP.UTF-8"), Some(locale("ja-JP")));
+    assert_eq!(negotiate::parse_posix("de_DE@euro"), Some(locale("de-DE")));
+    assert_eq!(negotiate::parse_posix("C"), None);
+    assert_eq!(negotiate::parse_posix("POSIX.UTF-8"), None);
+}
@@ -0,0 +1,112 @@
+// The lookup and formatting core as a `no_std` user sees it: this crate
+// links only `core` and `alloc`. `cargo test --no-default-features` also
+// builds the library itself without `std`.
+#![no_std]
+
+extern crate alloc;
+extern crate phrases;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use phrases::english::{self, English};
+use phrases::japanese::{self, script, Japanese};
+use phrases::{
+    negotiate, width, Args, Catalog, Catalogs, Date, DateStyle, FixedClock, Locale, PhraseKey,
+    Phrasebook, PluralCategory, Register, Resolver, Time, Value,
+};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+#[test]
+fn english_and_japanese_tables_are_built_in() {
+    assert_eq!(English.phrase(PhraseKey::Hello), Some("Hello!".to_string()));
+    assert_eq!(
+        Japanese.phrase(PhraseKey::Hello),
+        Some("こんにちは".to_string())
+    );
+    assert_eq!(english::greetings::hello_to(), "Hello, {name}!");
+    assert_eq!(japanese::farewells::goodbye(), "さようなら");
+    assert_eq!(
+        english::farewells::farewells(PluralCategory::One),
+        "{count} farewell"
+    );
+    // Japanese takes a category too, and has one form for all of them.
+    for &category in &[PluralCategory::One, PluralCategory::Other] {
+        assert_eq!(
+            japanese::farewells::farewells(category),
+            "さようなら{count}回"
+        );
+    }
+    assert_eq!(script::romaji("さようなら"), "sayōnara");
+}
+
+#[test]
+fn lookups_fall_back_and_choose_forms() {
+    assert_eq!(
+        phrases::lookup(&locale("ja-JP"), PhraseKey::Hello),
+        Some("こんにちは".to_string())
+    );
+    assert_eq!(
+        phrases::lookup_with_register(&locale("ja"), PhraseKey::Hello, Register::Casual),
+        Some("やあ".to_string())
+    );
+    assert_eq!(
+        phrases::greeting(&locale("en"), &FixedClock(Time::new(8, 30).unwrap())),
+        Some("Good morning!".to_string())
+    );
+
+    let resolved = Resolver::new().resolve(&locale("xx"), PhraseKey::Goodbye);
+    assert_eq!(resolved.map(|resolved| resolved.locale), Some(locale("en")));
+}
+
+#[test]
+fn phrases_format_with_typed_arguments_and_selects() {
+    let args = Args::new()
+        .with("name", "Ana")
+        .with_attribute("gender", "female");
+    assert_eq!(
+        phrases::format(&locale("es"), PhraseKey::Welcome, &args).unwrap(),
+        "¡Bienvenida, Ana!"
+    );
+
+    let text = phrases::format_count(&locale("en"), PhraseKey::Farewells, 1200, &Args::new());
+    assert_eq!(text.unwrap(), "1,200 farewells");
+
+    let args = Args::new().with_value(
+        "name",
+        Value::Date(Date::new(2026, 3, 1).unwrap(), DateStyle::Long),
+    );
+    assert_eq!(
+        phrases::format(&locale("ja"), PhraseKey::HelloTo, &args).unwrap(),
+        "こんにちは、2026年3月1日さん"
+    );
+}
+
+#[test]
+fn catalogs_parse_from_text() {
+    let catalog = Catalog::parse(locale("it"), "[greetings]\nhello = Ciao!\n").unwrap();
+    let mut catalogs = Catalogs::new();
+    catalogs.insert(catalog);
+    let resolved = Resolver::new()
+        .with_catalogs(catalogs)
+        .resolve(&locale("it-CH"), PhraseKey::Hello)
+        .unwrap();
+    assert_eq!(resolved.text, "Ciao!");
+}
+
+#[test]
+fn text_helpers_need_no_std() {
+    assert_eq!(width::width("さようなら"), 10);
+    assert_eq!(width::truncate("さようなら", 5), "さよ");
+
+    let preferences = negotiate::parse_accept_language("fr;q=0.5, ja");
+    let locales: Vec<String> = preferences
+        .iter()
+        .filter_map(|preference| preference.locale.as_ref())
+        .map(|locale| locale.to_string())
+        .collect();
+    assert_eq!(locales, ["ja", "fr"]);
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "std")]
+
+extern crate phrases;
+
+use std::sync::{Arc, Mutex};
+
+use phrases::observe::Stats;
+use phrases::{Counter, Event, Locale, Observer, Outcome, PhraseKey, Resolver};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+// Keeps every event as (key, requested, outcome) with the locales as tags.
+#[derive(Debug, Default)]
+struct Recorder {
+    events: Mutex<Vec<(PhraseKey, String, String)>>,
+}
+
+impl Observer for Recorder {
+    fn observe(&self, event: &Event) {
+        let outcome = match event.outcome {
+            Outcome::Hit(served) => format!("hit {}", served),
+            Outcome::Fallback(served) => format!("fallback {}", served),
+            Outcome::Miss => "miss".to_string(),
+        };
+        self.events
+            .lock()
+            .unwrap()
+            .push((event.key, event.requested.to_string(), outcome));
+    }
+}
+
+#[test]
+fn lookups_are_classified_by_the_locale_that_served_them() {
+    let recorder = Arc::new(Recorder::default());
+    let resolver = Resolver::with_default(locale("en")).with_observer(recorder.clone());
+    resolver.resolve(&locale("ja-JP"), PhraseKey::Hello);
+    resolver.resolve(&locale("ja"), PhraseKey::Hi);
+    resolver.resolve(&locale("ko"), PhraseKey::Goodbye);
+    let _ = resolver.format_count(
+        &locale("de"),
+        PhraseKey::Farewells,
+        2,
+        &phrases::Args::new(),
+    );
+
+    let missing = Resolver::with_default(locale("xx")).with_observer(recorder.clone());
+    missing.resolve(&locale("yy"), PhraseKey::Hello);
+
+    assert_eq!(
+        *recorder.events.lock().unwrap(),
+        [
+            (PhraseKey::Hello, "ja-JP".to_string(), "hit ja".to_string()),
+            (PhraseKey::Hi, "ja".to_string(), "fallback en".to_string()),
+            (
+                PhraseKey::Goodbye,
+                "ko".to_string(),
+                "fallback en".to_string()
+            ),
+            (PhraseKey::Farewells, "de".to_string(), "hit de".to_string()),
+            (PhraseKey::Hello, "yy".to_string(), "miss".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn events_compare_languages_not_regions() {
+    let (en_gb, en) = (locale("en-GB"), locale("en"));
+    assert_eq!(
+        Event::new(PhraseKey::Hello, &en_gb, Some(&en)).outcome,
+        Outcome::Hit(&en)
+    );
+    let ja = locale("ja");
+    assert_eq!(
+        Event::new(PhraseKey::Hello, &ja, Some(&en)).outcome,
+        Outcome::Fallback(&en)
+    );
+    assert_eq!(
+        Event::new(PhraseKey::Hello, &ja, None).outcome,
+        Outcome::Miss
+    );
+}
+
+#[test]
+fn counters_count_per_requested_locale_and_key() {
+    let counter = Arc::new(Counter::new());
+    let resolver = Resolver::new().with_observer(counter.clone());
+    for _ in 0..3 {
+        resolver.resolve(&locale("ja"), PhraseKey::Hello);
+    }
+    resolver.resolve(&locale("ja"), PhraseKey::Hi);
+    resolver.resolve(&locale("ja"), PhraseKey::Hi);
+    Resolver::with_default(locale("xx"))
+        .with_observer(counter.clone())
+        .resolve(&locale("yy"), PhraseKey::Hello);
+
+    assert_eq!(
+        counter.get(&locale("ja"), PhraseKey::Hello),
+        Stats {
+            hits: 3,
+            fallbacks: 0,
+            misses: 0,
+        }
+    );
+    assert_eq!(counter.get(&locale("ja"), PhraseKey::Hi).fallbacks, 2);
+    assert_eq!(counter.get(&locale("yy"), PhraseKey::Hello).total(), 1);
+    assert_eq!(
+        counter.get(&locale("fr"), PhraseKey::Hello),
+        Stats::default()
+    );
+
+    let missing: Vec<(String, PhraseKey)> = counter
+        .missing()
+        .into_iter()
+        .map(|(locale, key, _)| (locale.to_string(), key))
+        .collect();
+    assert_eq!(
+        missing,
+        [
+            ("ja".to_string(), PhraseKey::Hi),
+            ("yy".to_string(), PhraseKey::Hello),
+        ]
+    );
+
+    assert_eq!(
+        counter.to_json(),
+        concat!(
+            "[{\"locale\":\"ja\",\"key\":\"greetings.hello\",\"hits\":3,\"fallbacks\":0,\"misses\":0},",
+            "{\"locale\":\"ja\",\"key\":\"greetings.hi\",\"hits\":0,\"fallbacks\":2,\"misses\":0},",
+            "{\"locale\":\"yy\",\"key\":\"greetings.hello\",\"hits\":0,\"fallbacks\":0,\"misses\":1}]"
+        )
+    );
+
+    counter.reset();
+    assert_eq!(counter.stats(), []);
+    assert_eq!(counter.to_json(), "[]");
+}
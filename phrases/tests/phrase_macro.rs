@@ -0,0 +1,94 @@
+#![cfg(feature = "std")]
+
+extern crate phrases;
+#[macro_use]
+extern crate phrases_macros;
+
+use phrases::{context, Locale, PhraseKey};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+struct User {
+    name: &'static str,
+    gender: &'static str,
+}
+
+const ANA: User = User {
+    name: "Ana",
+    gender: "female",
+};
+
+#[test]
+fn formats_for_the_ambient_locale() {
+    context::with_locale(locale("ja-JP"), || {
+        assert_eq!(phrase!("greetings.hello"), "こんにちは");
+        assert_eq!(
+            phrase!("greetings.hello_to", name = ANA.name),
+            "こんにちは、Anaさん"
+        );
+    });
+    context::with_locale(locale("de"), || {
+        assert_eq!(
+            phrase!("greetings.hello_to", name = "Welt",),
+            "Hallo, Welt!"
+        );
+    });
+}
+
+#[test]
+fn counts_choose_plural_forms_and_attributes_choose_variants() {
+    context::with_locale(locale("en"), || {
+        assert_eq!(phrase!("farewells.farewells", count = 1), "1 farewell");
+        assert_eq!(
+            phrase!("farewells.farewells", count = 1200),
+            "1,200 farewells"
+        );
+    });
+    context::with_locale(locale("es"), || {
+        assert_eq!(
+            phrase!("greetings.welcome", name = ANA.name; gender = ANA.gender),
+            "¡Bienvenida, Ana!"
+        );
+        assert_eq!(
+            phrase!("greetings.welcome", name = "Luis"; gender = "male"),
+            "¡Bienvenido, Luis!"
+        );
+    });
+}
+
+#[test]
+fn scopes_nest_and_win_over_the_process_locale() {
+    context::set_locale(locale("fr"));
+    context::with_locale(locale("de"), || {
+        context::with_locale(locale("ja"), || {
+            assert_eq!(context::locale(), locale("ja"));
+        });
+        assert_eq!(context::locale(), locale("de"));
+        assert_eq!(phrase!("farewells.goodbye"), "Auf Wiedersehen.");
+    });
+
+    let result = std::panic::catch_unwind(|| {
+        context::with_locale(locale("zh"), || panic!("while formatting"))
+    });
+    assert!(result.is_err());
+    assert_eq!(context::locale(), locale("fr"));
+}
+
+#[test]
+fn locales_without_a_translation_fall_back_to_english() {
+    context::with_locale(locale("ko"), || {
+        assert_eq!(
+            phrase!("farewells.goodbye_to", name = "Sam"),
+            "Goodbye, Sam."
+        );
+    });
+}
+
+#[test]
+fn placeholders_cover_every_form_of_a_key() {
+    assert_eq!(PhraseKey::Hello.placeholders(), Vec::<String>::new());
+    assert_eq!(PhraseKey::HelloTo.placeholders(), ["name"]);
+    assert_eq!(PhraseKey::Farewells.placeholders(), ["count"]);
+}
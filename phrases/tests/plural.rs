@@ -0,0 +1,114 @@
+extern crate phrases;
+
+use phrases::plural;
+use phrases::Locale;
+use phrases::PluralCategory::{self, Few, Many, One, Other, Two, Zero};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn categories(tag: &str, counts: &[u64]) -> Vec<PluralCategory> {
+    counts
+        .iter()
+        .map(|&count| plural::category(&locale(tag), count))
+        .collect()
+}
+
+#[test]
+fn one_other_languages_single_out_one() {
+    for tag in ["en", "de-AT", "es", "xx"].iter() {
+        assert_eq!(
+            categories(tag, &[0, 1, 2, 11, 21, 101]),
+            [Other, One, Other, Other, Other, Other],
+            "{}",
+            tag
+        );
+        assert_eq!(plural::categories(&locale(tag)), [One, Other]);
+    }
+    // French counts zero with one; Japanese has no plural forms at all.
+    assert_eq!(categories("fr", &[0, 1, 2]), [One, One, Other]);
+    assert_eq!(categories("ja", &[0, 1, 2]), [Other, Other, Other]);
+    assert_eq!(plural::categories(&locale("ja")), [Other]);
+}
+
+#[test]
+fn east_slavic_languages_go_by_the_last_digits() {
+    for tag in ["ru", "uk"].iter() {
+        assert_eq!(categories(tag, &[1, 21, 101, 1001]), [One; 4], "{}", tag);
+        assert_eq!(categories(tag, &[2, 3, 4, 22, 104]), [Few; 5], "{}", tag);
+        assert_eq!(categories(tag, &[0, 5, 9, 10, 100]), [Many; 5], "{}", tag);
+        // The teens are `Many` whatever their last digit.
+        assert_eq!(
+            categories(tag, &[11, 12, 13, 14, 111, 112]),
+            [Many; 6],
+            "{}",
+            tag
+        );
+    }
+    assert_eq!(plural::categories(&locale("ru")), [One, Few, Many, Other]);
+}
+
+#[test]
+fn arabic_uses_every_category() {
+    assert_eq!(categories("ar", &[0, 1, 2]), [Zero, One, Two]);
+    assert_eq!(categories("ar", &[3, 7, 10, 103, 110]), [Few; 5]);
+    assert_eq!(categories("ar", &[11, 50, 99, 111, 999]), [Many; 5]);
+    assert_eq!(categories("ar", &[100, 101, 102, 200]), [Other; 4]);
+    assert_eq!(plural::categories(&locale("ar-EG")), PluralCategory::all());
+}
+
+// The `Plural-Forms` expressions, written out in Rust.
+fn gettext_index(tag: &str, n: u64) -> usize {
+    match tag {
+        "ja" => 0,
+        "en" => (n != 1) as usize,
+        "fr" => (n > 1) as usize,
+        "ru" => {
+            if n % 10 == 1 && n % 100 != 11 {
+                0
+            } else if n % 10 >= 2 && n % 10 <= 4 && (n % 100 < 12 || n % 100 > 14) {
+                1
+            } else {
+                2
+            }
+        }
+        "ar" => match n {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            _ if n % 100 >= 3 && n % 100 <= 10 => 3,
+            _ if n % 100 >= 11 => 4,
+            _ => 5,
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn gettext_plural_forms_pick_the_same_forms() {
+    assert_eq!(
+        plural::gettext_plural_forms(&locale("ja")),
+        "nplurals=1; plural=0;"
+    );
+    assert_eq!(
+        plural::gettext_plural_forms(&locale("en-GB")),
+        "nplurals=2; plural=(n != 1);"
+    );
+    assert_eq!(
+        plural::gettext_plural_forms(&locale("pt")),
+        "nplurals=2; plural=(n > 1);"
+    );
+    assert!(plural::gettext_plural_forms(&locale("uk")).starts_with("nplurals=4;"));
+    assert!(plural::gettext_plural_forms(&locale("ar")).starts_with("nplurals=6;"));
+
+    // Form indices follow the order of `categories`.
+    for tag in ["ja", "en", "fr", "ru", "ar"].iter() {
+        let locale = locale(tag);
+        let forms = plural::categories(&locale);
+        for n in 0..1000 {
+            let category = plural::category(&locale, n);
+            assert_eq!(forms[gettext_index(tag, n)], category, "{} for {}", tag, n);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+extern crate phrases;
+
+use phrases::english::English;
+use phrases::message;
+use phrases::{Args, Locale, PhraseKey, Phrasebook, PluralCategory, Pseudo};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+#[test]
+fn accented_text_is_bracketed_and_lengthened_by_a_third() {
+    // Five letters take two `!`, ten take three; there is always one.
+    assert_eq!(Pseudo::Accented.transform("Hello"), "[Ĥéļļö!! ]");
+    assert_eq!(Pseudo::Accented.transform("Good day."), "[Ĝööð ðáý.!!! ]");
+    assert_eq!(Pseudo::Accented.transform("123"), "[123! ]");
+    assert_eq!(
+        Pseudo::Accented.phrase(PhraseKey::Hello).unwrap(),
+        "[Ĥéļļö!!! ]"
+    );
+    assert_eq!(Pseudo::Accented.locale(), locale("en-XA"));
+}
+
+#[test]
+fn bidi_text_runs_are_overridden_right_to_left() {
+    assert_eq!(
+        Pseudo::Bidi.transform("Goodbye."),
+        "\u{200F}\u{202E}Goodbye.\u{202C}\u{200F}"
+    );
+    // Each run between placeholders is wrapped on its own.
+    assert_eq!(
+        Pseudo::Bidi.transform("Hello, {name}!"),
+        "\u{200F}\u{202E}Hello, \u{202C}{name}\u{202E}!\u{202C}\u{200F}"
+    );
+    assert_eq!(Pseudo::Bidi.locale(), locale("ar-XB"));
+}
+
+#[test]
+fn placeholders_survive_the_transform() {
+    for &pseudo in Pseudo::all() {
+        for &key in PhraseKey::all() {
+            let english = English.phrase(key).unwrap();
+            let text = pseudo.phrase(key).unwrap();
+            assert_eq!(
+                message::placeholders(&text).unwrap(),
+                message::placeholders(&english).unwrap(),
+                "{} {}",
+                pseudo.tag(),
+                key.name()
+            );
+        }
+    }
+
+    let text = Pseudo::Accented.transform("{count} farewells, {{literally}}");
+    assert_eq!(text, "[{count} ƒáŕéŵéļļš, {{ļîţéŕáļļý}}!!!!!! ]");
+    assert_eq!(
+        message::format(&text, &Args::new().with("count", 3)).unwrap(),
+        "[3 ƒáŕéŵéļļš, {ļîţéŕáļļý}!!!!!! ]"
+    );
+    // Plural forms English lacks take its `other` form.
+    assert_eq!(
+        Pseudo::Bidi.plural(PhraseKey::Farewells, PluralCategory::Few),
+        Pseudo::Bidi.plural(PhraseKey::Farewells, PluralCategory::Other)
+    );
+}
+
+#[test]
+fn selects_keep_their_structure_and_rewrite_each_variant() {
+    let template = "{gender, select, female {Welcome, {name}!} other {Hi, {name}}} {{ok}}";
+    let accented = Pseudo::Accented.transform(template);
+    assert_eq!(
+        accented,
+        "[{gender, select, female {Ŵéļçöɱé, {name}!} other {Ĥî, {name}}} {{öķ}}!!!! ]"
+    );
+    let args = |gender: &str| {
+        Args::new()
+            .with("name", "Ana")
+            .with_attribute("gender", gender)
+    };
+    assert_eq!(
+        message::format(&accented, &args("female")).unwrap(),
+        "[Ŵéļçöɱé, Ana! {öķ}!!!! ]"
+    );
+    assert_eq!(
+        message::format(&accented, &args("male")).unwrap(),
+        "[Ĥî, Ana {öķ}!!!! ]"
+    );
+
+    let mirrored = Pseudo::Bidi.transform(template);
+    assert_eq!(
+        message::format(&mirrored, &args("other")).unwrap(),
+        "\u{200F}\u{202E}Hi, \u{202C}Ana\u{202E} {ok}\u{202C}\u{200F}"
+    );
+    assert_eq!(message::selectors(&mirrored).unwrap(), ["gender"]);
+}
@@ -0,0 +1,23 @@
+extern crate phrases;
+
+use phrases::english::English;
+use phrases::japanese::Japanese;
+use phrases::register;
+use phrases::{PhraseKey, Register};
+
+#[test]
+fn the_built_in_phrasebooks_have_registers() {
+    assert_eq!(
+        register::select(&Japanese, PhraseKey::Hello, Register::Casual).unwrap(),
+        "やあ"
+    );
+    assert_eq!(
+        register::select(&English, PhraseKey::Hello, Register::Formal).unwrap(),
+        "Good day."
+    );
+    // Keys without a variant in the register fall back to the neutral one.
+    assert_eq!(
+        register::select(&English, PhraseKey::HelloTo, Register::Casual).unwrap(),
+        "Hello, {name}!"
+    );
+}
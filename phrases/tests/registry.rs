@@ -0,0 +1,100 @@
+#![cfg(feature = "std")]
+
+extern crate phrases;
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use phrases::{Locale, PhraseKey, PhraseRegistry};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("phrases-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn reloads_after_edits_and_deletions_while_readers_hold_snapshots() {
+    let dir = scratch_dir("reload");
+    let (fr, de) = (dir.join("fr.catalog"), dir.join("de.catalog"));
+    fs::write(&fr, "[greetings]\nhello = Salut !\n").unwrap();
+    fs::write(&de, "[greetings]\nhello = Servus!\n").unwrap();
+
+    let registry = Arc::new(PhraseRegistry::load_dir(&dir).unwrap());
+    assert!(!registry.reload_if_changed().unwrap());
+    let before = registry.snapshot();
+
+    // Readers check that every snapshot they see is whole: French and
+    // German are either both from the files or, after the reloads, French
+    // edited and German back to the built-in catalog.
+    let stop = Arc::new(AtomicBool::new(false));
+    let started = Arc::new(Barrier::new(5));
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let (registry, stop, started) = (registry.clone(), stop.clone(), started.clone());
+            thread::spawn(move || {
+                started.wait();
+                while !stop.load(Ordering::Relaxed) {
+                    let snapshot = registry.snapshot();
+                    let fr = snapshot.phrase(&locale("fr"), PhraseKey::Hello).unwrap();
+                    let de = snapshot.phrase(&locale("de"), PhraseKey::Hello).unwrap();
+                    assert!(
+                        ["Salut !", "Coucou!"].contains(&fr) && ["Servus!", "Hallo!"].contains(&de),
+                        "{} {}",
+                        fr,
+                        de
+                    );
+                }
+            })
+        })
+        .collect();
+    started.wait();
+
+    // An edit that keeps both the modification time and the length is
+    // still seen.
+    let modified = fs::metadata(&fr).unwrap().modified().unwrap();
+    fs::write(&fr, "[greetings]\nhello = Coucou!\n").unwrap();
+    File::options()
+        .write(true)
+        .open(&fr)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    assert!(registry.reload_if_changed().unwrap());
+    assert!(!registry.reload_if_changed().unwrap());
+    assert_eq!(
+        registry.snapshot().phrase(&locale("fr"), PhraseKey::Hello),
+        Some("Coucou!")
+    );
+
+    // So is removing a file, however old.
+    fs::remove_file(&de).unwrap();
+    assert!(registry.reload_if_changed().unwrap());
+    assert_eq!(
+        registry.snapshot().phrase(&locale("de"), PhraseKey::Hello),
+        Some("Hallo!")
+    );
+
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(
+        before.phrase(&locale("fr"), PhraseKey::Hello),
+        Some("Salut !")
+    );
+    assert_eq!(
+        before.phrase(&locale("de"), PhraseKey::Hello),
+        Some("Servus!")
+    );
+    assert_eq!(registry.snapshot().generation(), before.generation() + 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
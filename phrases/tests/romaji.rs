@@ -0,0 +1,123 @@
+extern crate phrases;
+
+use phrases::japanese::{self, script};
+use phrases::PhraseKey;
+
+fn assert_romaji(cases: &[(&str, &str)]) {
+    for &(kana, expected) in cases {
+        assert_eq!(script::romaji(kana), expected, "romaji of {}", kana);
+    }
+}
+
+#[test]
+fn basic_syllables() {
+    assert_romaji(&[
+        ("あいうえお", "aiueo"),
+        ("かきくけこ", "kakikukeko"),
+        ("さしすせそ", "sashisuseso"),
+        ("たちつてと", "tachitsuteto"),
+        ("なにぬねの", "naninuneno"),
+        ("はひふへほ", "hahifuheho"),
+        ("まみむめも", "mamimumemo"),
+        ("やゆよ", "yayuyo"),
+        ("らりるれろ", "rarirurero"),
+        ("わを", "wao"),
+        ("がぎぐげご", "gagigugego"),
+        ("ざじずぜぞ", "zajizuzezo"),
+        ("だぢづでど", "dajizudedo"),
+        ("ばびぶべぼ", "babibubebo"),
+        ("ぱぴぷぺぽ", "papipupepo"),
+    ]);
+}
+
+#[test]
+fn youon() {
+    assert_romaji(&[
+        ("きゃきゅきょ", "kyakyukyo"),
+        ("しゃしゅしょ", "shashusho"),
+        ("ちゃちゅちょ", "chachucho"),
+        ("じゃじゅじょ", "jajujo"),
+        ("にゃにゅにょ", "nyanyunyo"),
+        ("ひゃびゅぴょ", "hyabyupyo"),
+        ("みゃりゅぎょ", "myaryugyo"),
+        ("とうきょう", "tōkyō"),
+    ]);
+}
+
+#[test]
+fn long_vowels() {
+    assert_romaji(&[
+        ("おかあさん", "okāsan"),
+        ("すうがく", "sūgaku"),
+        ("おねえさん", "onēsan"),
+        ("おおきい", "ōkii"),
+        ("こうえん", "kōen"),
+        ("せんせい", "sensei"),
+        ("コーヒー", "kōhī"),
+        ("ラーメン", "rāmen"),
+    ]);
+}
+
+#[test]
+fn small_tsu() {
+    assert_romaji(&[
+        ("がっこう", "gakkō"),
+        ("きって", "kitte"),
+        ("ざっし", "zasshi"),
+        ("まっちゃ", "matcha"),
+        ("いっぱい", "ippai"),
+        ("ベッド", "beddo"),
+    ]);
+}
+
+#[test]
+fn syllabic_n() {
+    assert_romaji(&[
+        ("ほん", "hon"),
+        ("きんえん", "kin'en"),
+        ("こんや", "kon'ya"),
+        ("しんぶん", "shinbun"),
+    ]);
+}
+
+#[test]
+fn katakana_loanwords() {
+    assert_romaji(&[
+        ("ファイル", "fairu"),
+        ("パーティー", "pātī"),
+        ("ウェブ", "webu"),
+        ("ヴァイオリン", "vaiorin"),
+        ("チェック", "chekku"),
+    ]);
+}
+
+#[test]
+fn greetings_read_the_particle_as_wa() {
+    assert_romaji(&[("こんにちは", "konnichiwa"), ("こんばんは", "konbanwa")]);
+}
+
+#[test]
+fn non_kana_passes_through() {
+    assert_romaji(&[
+        ("こんにちは、{name}さん", "konnichiwa, {name}san"),
+        ("失礼いたします", "失礼itashimasu"),
+        ("はい。", "hai."),
+    ]);
+}
+
+#[test]
+fn converts_between_hiragana_and_katakana() {
+    assert_eq!(script::to_katakana("こんにちは"), "コンニチハ");
+    assert_eq!(script::to_hiragana("コーヒー"), "こーひー");
+    assert_eq!(script::to_katakana("Hello, {name}"), "Hello, {name}");
+}
+
+#[test]
+fn romanizes_built_in_phrases() {
+    assert_eq!(japanese::romanized(PhraseKey::Hello), "konnichiwa");
+    assert_eq!(japanese::romanized(PhraseKey::Goodbye), "sayōnara");
+    assert_eq!(
+        japanese::romanized(PhraseKey::GoodMorning),
+        "ohayōgozaimasu"
+    );
+}
@@ -0,0 +1,170 @@
+extern crate phrases;
+
+use phrases::fluent::Bundle;
+use phrases::message::{self, FormatError};
+use phrases::{Args, Locale, PhraseKey, Register, Resolver, Value};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+const WELCOME: &str = "{gender, select, female {Bienvenida, {name}} \
+                       male {Bienvenido, {name}} other {Te damos la bienvenida, {name}}}";
+
+#[test]
+fn selects_variants_by_attribute() {
+    let args = |gender: &str| {
+        Args::new()
+            .with("name", "Ana")
+            .with_attribute("gender", gender)
+    };
+    assert_eq!(
+        message::format(WELCOME, &args("female")).unwrap(),
+        "Bienvenida, Ana"
+    );
+    assert_eq!(
+        message::format(WELCOME, &args("male")).unwrap(),
+        "Bienvenido, Ana"
+    );
+    assert_eq!(
+        message::format(WELCOME, &args("nonbinary")).unwrap(),
+        "Te damos la bienvenida, Ana"
+    );
+    // Without the attribute, `other` is chosen.
+    assert_eq!(
+        message::format(WELCOME, &Args::new().with("name", "Ana")).unwrap(),
+        "Te damos la bienvenida, Ana"
+    );
+}
+
+#[test]
+fn templates_without_a_select_ignore_attributes() {
+    let args = Args::new()
+        .with("name", "Ana")
+        .with_attribute("gender", "female");
+    assert_eq!(
+        message::format("Welcome, {name}!", &args).unwrap(),
+        "Welcome, Ana!"
+    );
+}
+
+#[test]
+fn selects_nest_and_may_choose_by_argument() {
+    let template = "{kind, select, file {{count, select, 1 {one file} other {{count} files}}} \
+                    other {something}}";
+    let args = Args::new()
+        .with("kind", "file")
+        .with_value("count", Value::Number(1.0));
+    assert_eq!(message::format(template, &args).unwrap(), "one file");
+
+    let args = Args::new()
+        .with("kind", "file")
+        .with_value("count", Value::Number(2500.0));
+    assert_eq!(
+        message::format_localized(template, &args, &locale("de")).unwrap(),
+        "2.500 files"
+    );
+
+    assert_eq!(
+        message::placeholders(template).unwrap(),
+        vec!["count".to_string()]
+    );
+    assert_eq!(
+        message::selectors(template).unwrap(),
+        vec!["kind".to_string(), "count".to_string()]
+    );
+}
+
+#[test]
+fn malformed_selects_are_syntax_errors() {
+    let error = |template: &str| match message::format(template, &Args::new()) {
+        Err(FormatError::Syntax { offset, message }) => (offset, message),
+        other => panic!("expected a syntax error, got {:?}", other),
+    };
+    assert_eq!(
+        error("Hi {gender, select, female {Ana}}").1,
+        "a select needs an `other` variant"
+    );
+    assert_eq!(error("{gender, plural, other {x}}").0, 0);
+    assert_eq!(error("{gender, select, other {x}").1, "unclosed `{`");
+    assert_eq!(error("{gender, select, other x}").0, 23);
+}
+
+#[test]
+fn deeply_nested_selects_are_refused_without_overflowing() {
+    let nested = |depth: usize| {
+        format!(
+            "{}x{}",
+            "{a, select, other {".repeat(depth),
+            "}}".repeat(depth)
+        )
+    };
+    assert_eq!(message::format(&nested(10), &Args::new()).unwrap(), "x");
+    match message::format(&nested(200_000), &Args::new()) {
+        Err(FormatError::Syntax { offset, message }) => {
+            assert_eq!(message, "selects nested too deeply");
+            assert_eq!(offset, 64 * "{a, select, other {".len());
+        }
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    assert!(message::placeholders(&nested(200_000)).is_err());
+
+    // Nor do catalogs holding one, as `lint` and `--catalogs` read them.
+    let source = format!("[greetings]\nhello = {}\n", nested(200_000));
+    let diagnostics = phrases::lint::lint(&locale("de"), source.as_bytes(), None);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.contains("nested too deeply"));
+}
+
+#[test]
+fn catalogs_choose_variants_by_caller_attributes() {
+    let resolver = Resolver::new();
+    let args = Args::new()
+        .with("name", "Luis")
+        .with_attribute("gender", "male");
+    assert_eq!(
+        resolver
+            .format(&locale("es-MX"), PhraseKey::Welcome, &args)
+            .unwrap()
+            .text,
+        "¡Bienvenido, Luis!"
+    );
+    assert_eq!(
+        phrases::format(&locale("ja"), PhraseKey::Welcome, &args).unwrap(),
+        "ようこそ、Luisさん"
+    );
+
+    let formal =
+        phrases::lookup_with_register(&locale("fr"), PhraseKey::Welcome, Register::Formal).unwrap();
+    let args = Args::new()
+        .with("name", "Ana")
+        .with_attribute("gender", "female");
+    assert_eq!(
+        message::format(&formal, &args).unwrap(),
+        "Soyez la bienvenue, Ana !"
+    );
+}
+
+#[test]
+fn fluent_selects_use_attributes_and_become_catalog_selects() {
+    let bundle = Bundle::parse(
+        locale("es"),
+        "greetings-welcome = { $gender ->\n    [female] Bienvenida, { $name }\n   *[other] Hola, { $name }\n}\n",
+    )
+    .unwrap();
+    let args = Args::new()
+        .with("name", "Ana")
+        .with_attribute("gender", "female");
+    assert_eq!(
+        bundle.format("greetings-welcome", &args).unwrap(),
+        "Bienvenida, Ana"
+    );
+
+    let catalog = bundle.to_catalog().unwrap();
+    let template = catalog.get("greetings.welcome").unwrap();
+    assert_eq!(
+        template,
+        "{gender, select, female {Bienvenida, {name}} other {Hola, {name}}}"
+    );
+    assert_eq!(message::format(template, &args).unwrap(), "Bienvenida, Ana");
+}
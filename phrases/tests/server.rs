@@ -0,0 +1,161 @@
+#![cfg(feature = "std")]
+
+extern crate phrases;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use phrases::server::Server;
+use phrases::{Counter, PhraseRegistry};
+
+fn start() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = Server::new(Arc::new(PhraseRegistry::builtin()));
+    thread::spawn(move || server.serve(listener));
+    address
+}
+
+// Sends a raw request and splits the reply into status, head and body.
+fn request(address: SocketAddr, head: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(head.as_bytes()).unwrap();
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).unwrap();
+
+    let at = reply.find("\r\n\r\n").unwrap();
+    let (head, body) = (reply[..at].to_string(), reply[at + 4..].to_string());
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, head, body)
+}
+
+fn get(address: SocketAddr, path: &str) -> (u16, String, String) {
+    request(
+        address,
+        &format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path),
+    )
+}
+
+fn etag(head: &str) -> String {
+    head.lines()
+        .find(|line| line.starts_with("ETag: "))
+        .map(|line| line["ETag: ".len()..].to_string())
+        .unwrap()
+}
+
+#[test]
+fn lists_locales() {
+    let (status, _, body) = get(start(), "/locales");
+    assert_eq!(status, 200);
+    assert!(body.contains(r#"{"locale":"ja","direction":"ltr"}"#));
+    assert!(body.contains(r#"{"locale":"ar-XB","direction":"rtl"}"#));
+}
+
+#[test]
+fn serves_phrases_with_fallback_and_arguments() {
+    let address = start();
+
+    let (status, _, body) = get(address, "/phrases/ja-JP/greetings.hello");
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        r#"{"key":"greetings.hello","locale":"ja-JP","served_by":"ja","direction":"ltr","text":"こんにちは"}"#
+    );
+
+    let (_, _, body) = get(address, "/phrases/en/hello_to?name=Ada%20L");
+    assert!(body.contains(r#""text":"Hello, Ada L!""#));
+
+    let (_, _, body) = get(address, "/phrases/en/hello_to?name=Ada+L%2B");
+    assert!(body.contains(r#""text":"Hello, Ada L+!""#), "{}", body);
+
+    let (_, _, body) = get(address, "/phrases/en/farewells?count=1");
+    assert!(body.contains(r#""text":"1 farewell""#), "{}", body);
+}
+
+#[test]
+fn reports_errors_as_json() {
+    let address = start();
+    assert_eq!(get(address, "/phrases/en/nope").0, 404);
+    assert_eq!(get(address, "/phrases/not a locale/hello").0, 400);
+    assert_eq!(get(address, "/phrases/en/hello_to").0, 400);
+    assert_eq!(get(address, "/elsewhere").0, 404);
+
+    // Only the query reads `+` as a space.
+    let (status, _, body) = get(address, "/phrases/en/hello+there");
+    assert_eq!(status, 404);
+    assert!(body.contains("unknown phrase `hello+there`"), "{}", body);
+
+    let (status, head, _) = request(address, "POST /locales HTTP/1.1\r\n\r\n");
+    assert_eq!(status, 405);
+    assert!(head.contains("Allow: GET, HEAD"));
+
+    let (status, _, body) = get(address, "/phrases/en/farewells?count=2&register=casual");
+    assert_eq!(status, 400);
+    assert!(body.contains("cannot be combined"), "{}", body);
+}
+
+#[test]
+fn refuses_overlong_request_heads_without_a_line_break() {
+    let address = start();
+    // One byte over the limit, all on the request line.
+    let mut head = "GET /".to_string();
+    head.push_str(&"a".repeat(8 * 1024 + 1 - head.len()));
+    let (status, _, body) = request(address, &head);
+    assert_eq!(status, 400);
+    assert!(body.contains("too large"), "{}", body);
+}
+
+#[test]
+fn answers_matching_etags_with_not_modified() {
+    let address = start();
+    let (_, head, body) = get(address, "/phrases/fr/hello");
+    let tag = etag(&head);
+
+    let (status, head, empty) = request(
+        address,
+        &format!(
+            "GET /phrases/fr/hello HTTP/1.1\r\nIf-None-Match: {}\r\n\r\n",
+            tag
+        ),
+    );
+    assert_eq!(status, 304);
+    assert_eq!(etag(&head), tag);
+    assert!(empty.is_empty());
+    // A 304 describes no body.
+    assert!(!head.contains("Content-Length"), "{}", head);
+    assert!(!head.contains("Content-Type"), "{}", head);
+
+    let (status, _, again) = request(
+        address,
+        "GET /phrases/fr/hello HTTP/1.1\r\nIf-None-Match: \"stale\"\r\n\r\n",
+    );
+    assert_eq!(status, 200);
+    assert_eq!(again, body);
+}
+
+#[test]
+fn counts_lookups_when_collecting_statistics() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let counter = Arc::new(Counter::new());
+    let registry = PhraseRegistry::builtin().with_observer(counter.clone());
+    let server = Server::new(Arc::new(registry)).with_stats(counter);
+    thread::spawn(move || server.serve(listener));
+
+    get(address, "/phrases/ja-JP/hello");
+    get(address, "/phrases/ja/hi");
+    get(address, "/phrases/ja/hi");
+
+    let (status, _, body) = get(address, "/stats");
+    assert_eq!(status, 200);
+    assert!(body.contains(
+        r#"{"locale":"ja-JP","key":"greetings.hello","hits":1,"fallbacks":0,"misses":0}"#
+    ));
+    assert!(
+        body.contains(r#"{"locale":"ja","key":"greetings.hi","hits":0,"fallbacks":2,"misses":0}"#)
+    );
+
+    assert_eq!(get(start(), "/stats").0, 404);
+}
@@ -0,0 +1,91 @@
+extern crate phrases;
+
+use phrases::{DayPeriod, FixedClock, Locale, PhraseKey, Resolver, Time};
+
+fn locale(tag: &str) -> Locale {
+    tag.parse().unwrap()
+}
+
+fn at(hour: u8, minute: u8) -> FixedClock {
+    FixedClock(Time::new(hour, minute).unwrap())
+}
+
+#[test]
+fn periods_change_on_the_hour() {
+    let periods = [
+        ((0, 0), DayPeriod::Evening),
+        ((4, 59), DayPeriod::Evening),
+        ((5, 0), DayPeriod::Morning),
+        ((11, 59), DayPeriod::Morning),
+        ((12, 0), DayPeriod::Afternoon),
+        ((17, 59), DayPeriod::Afternoon),
+        ((18, 0), DayPeriod::Evening),
+        ((23, 59), DayPeriod::Evening),
+    ];
+    for &((hour, minute), period) in periods.iter() {
+        assert_eq!(
+            DayPeriod::of(Time::new(hour, minute).unwrap()),
+            period,
+            "{:02}:{:02}",
+            hour,
+            minute
+        );
+    }
+    assert_eq!(DayPeriod::Morning.greeting(), PhraseKey::GoodMorning);
+    assert_eq!(DayPeriod::Evening.greeting(), PhraseKey::GoodEvening);
+    assert_eq!(Time::new(24, 0), None);
+    assert_eq!(Time::new(12, 60), None);
+}
+
+#[test]
+fn greetings_follow_the_clock_in_each_locale() {
+    let greetings = [
+        (
+            "en",
+            [
+                "Good evening!",
+                "Good morning!",
+                "Good afternoon!",
+                "Good evening!",
+            ],
+        ),
+        (
+            "ja-JP",
+            [
+                "こんばんは",
+                "おはようございます",
+                "こんにちは",
+                "こんばんは",
+            ],
+        ),
+        (
+            "de",
+            [
+                "Guten Abend!",
+                "Guten Morgen!",
+                "Guten Tag!",
+                "Guten Abend!",
+            ],
+        ),
+        ("fr", ["Bonsoir !", "Bonjour !", "Bonjour !", "Bonsoir !"]),
+    ];
+    let clocks = [at(4, 59), at(5, 0), at(12, 0), at(18, 0)];
+    for &(tag, ref expected) in greetings.iter() {
+        for (clock, &expected) in clocks.iter().zip(expected.iter()) {
+            assert_eq!(
+                phrases::greeting(&locale(tag), clock).unwrap(),
+                expected,
+                "{} at {:?}",
+                tag,
+                clock
+            );
+        }
+    }
+
+    // Through a resolver, a locale without greetings falls back to English.
+    let resolved = Resolver::new()
+        .greeting(&locale("ko"), &at(11, 59))
+        .unwrap();
+    assert_eq!(resolved.text, "Good morning!");
+    assert!(resolved.is_fallback());
+}
@@ -0,0 +1,66 @@
+extern crate phrases;
+
+use phrases::width::{self, Align};
+
+#[test]
+fn wide_characters_take_two_cells() {
+    assert_eq!(width::width("hello"), 5);
+    assert_eq!(width::width("さようなら"), 10);
+    assert_eq!(width::width("你好，世界"), 10);
+    assert_eq!(width::width("ＡＢ"), 4);
+    assert_eq!(width::width("안녕"), 4);
+    assert_eq!(width::width("🚀"), 2);
+    assert_eq!(width::width("☀✅"), 4);
+}
+
+#[test]
+fn combining_marks_and_jamo_take_no_cells_of_their_own() {
+    // `é` as `e` and a combining acute, and a stacked Vietnamese vowel.
+    assert_eq!(width::width("Cafe\u{301}"), 4);
+    assert_eq!(width::width("Vie\u{302}\u{323}t"), 4);
+    assert_eq!(width::graphemes("e\u{301}x").count(), 2);
+    // 한 spelled with conjoining jamo is one wide character.
+    assert_eq!(width::width("\u{1112}\u{1161}\u{11AB}"), 2);
+    assert_eq!(width::graphemes("\u{1112}\u{1161}\u{11AB}").count(), 1);
+    // Bidi controls, as pseudo-locales add them, are invisible.
+    assert_eq!(width::width("\u{200F}\u{202E}Hi\u{202C}\u{200F}"), 2);
+}
+
+#[test]
+fn joined_emoji_and_flags_are_single_characters() {
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    assert_eq!(width::graphemes(family).collect::<Vec<_>>(), [family]);
+    assert_eq!(width::width(family), 2);
+
+    let flags = "\u{1F1EF}\u{1F1F5}\u{1F1EB}\u{1F1F7}";
+    assert_eq!(
+        width::graphemes(flags).collect::<Vec<_>>(),
+        ["\u{1F1EF}\u{1F1F5}", "\u{1F1EB}\u{1F1F7}"]
+    );
+    assert_eq!(width::width(flags), 4);
+}
+
+#[test]
+fn truncation_never_splits_a_character() {
+    assert_eq!(width::truncate("さようなら", 4), "さよ");
+    // The third kana would straddle the limit, so it is left out.
+    assert_eq!(width::truncate("さようなら", 5), "さよ");
+    assert_eq!(width::truncate("Cafe\u{301}s", 4), "Cafe\u{301}");
+    assert_eq!(width::truncate("\u{1F1EF}\u{1F1F5}!", 1), "");
+    assert_eq!(width::truncate("hello", 10), "hello");
+
+    assert_eq!(width::truncate_with("さようなら", 7, "…"), "さよう…");
+    assert_eq!(width::truncate_with("さようなら", 6, "…"), "さよ…");
+    assert_eq!(width::truncate_with("さよ", 4, "…"), "さよ");
+}
+
+#[test]
+fn padding_counts_cells() {
+    assert_eq!(width::pad("さよ", 6, Align::Left), "さよ  ");
+    assert_eq!(width::pad("さよ", 6, Align::Right), "  さよ");
+    assert_eq!(width::pad("さよ", 7, Align::Center), " さよ  ");
+    assert_eq!(width::pad("さようなら", 4, Align::Left), "さようなら");
+    assert_eq!(width::fit("さようなら", 7, Align::Left), "さよう…");
+    assert_eq!(width::fit("さようなら", 6, Align::Right), " さよ…");
+    assert_eq!(width::width(&width::fit("🚀 launch", 5, Align::Left)), 5);
+}